@@ -0,0 +1,399 @@
+use crate::Result;
+use crate::inspect::chunk_range;
+use crate::stats::{Bound, bounds, float16};
+use parquet::basic::{LogicalType, SortOrder};
+use parquet::column::reader::{ColumnReader, ColumnReaderImpl};
+use parquet::data_type::{ByteArray, DataType as ParquetType, FixedLenByteArray};
+use parquet::file::metadata::ParquetMetaData;
+use parquet::file::properties::ReaderProperties;
+use parquet::file::reader::{ChunkReader, RowGroupReader};
+use parquet::file::serialized_reader::SerializedRowGroupReader;
+use parquet::file::statistics::Statistics;
+use parquet::schema::types::ColumnDescriptor;
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::Arc;
+
+const BATCH_SIZE: usize = 8192;
+
+/// Stored statistics of a column chunk next to those recomputed from its
+/// values, see [`ParquetViewerFile::audit_statistics`](crate::ParquetViewerFile::audit_statistics).
+///
+/// Min and max are rendered according to the sort order of the column, so
+/// unsigned integers are shown as such.
+#[derive(Debug, Clone)]
+pub struct ChunkStatisticsAudit {
+    pub row_group: usize,
+    /// Dotted path of the leaf column.
+    pub column: String,
+    /// Whether the column chunk has statistics at all.
+    pub has_statistics: bool,
+    /// Whether min and max come from the deprecated `min` and `max` fields,
+    /// which old writers ordered as signed values whatever the column type.
+    pub deprecated_min_max: bool,
+    pub stored_min: Option<String>,
+    pub stored_max: Option<String>,
+    pub stored_null_count: Option<u64>,
+    /// Smallest and largest non-null values, `None` for chunks of only nulls
+    /// or columns without a defined sort order.
+    pub actual_min: Option<String>,
+    pub actual_max: Option<String>,
+    pub actual_null_count: u64,
+    pub mismatches: Vec<StatisticsMismatch>,
+}
+
+impl ChunkStatisticsAudit {
+    /// Whether the stored statistics agree with the values of the chunk.
+    pub fn is_consistent(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Stored statistics that disagree with the values of a column chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatisticsMismatch {
+    /// The stored min is not the smallest value, or for truncated
+    /// statistics, not a lower bound of the values.
+    Min {
+        stored: String,
+        actual: Option<String>,
+        exact: bool,
+    },
+    /// The stored max is not the largest value, or for truncated
+    /// statistics, not an upper bound of the values.
+    Max {
+        stored: String,
+        actual: Option<String>,
+        exact: bool,
+    },
+    NullCount {
+        stored: u64,
+        actual: u64,
+    },
+    /// The stored min cannot be decoded, such as a Float16 value of less
+    /// than two bytes.
+    InvalidMin {
+        bytes: Vec<u8>,
+    },
+    /// The stored max cannot be decoded.
+    InvalidMax {
+        bytes: Vec<u8>,
+    },
+    /// The values of the column chunk cannot be read, so the stored
+    /// statistics were not checked and the actual ones are unknown.
+    Unreadable {
+        error: String,
+    },
+}
+
+impl fmt::Display for StatisticsMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Min { stored, actual, .. } | Self::Max { stored, actual, .. } => {
+                let (name, bound) = match self {
+                    Self::Min { .. } => ("min", "smallest"),
+                    _ => ("max", "largest"),
+                };
+                let truncated = match self {
+                    Self::Min { exact: false, .. } | Self::Max { exact: false, .. } => "truncated ",
+                    _ => "",
+                };
+                match actual {
+                    Some(actual) => write!(
+                        f,
+                        "{truncated}{name} {stored} does not match the {bound} value {actual}"
+                    ),
+                    None => write!(f, "{name} {stored} stored for a chunk of only nulls"),
+                }
+            }
+            Self::NullCount { stored, actual } => {
+                write!(f, "null count {stored} but {actual} nulls found")
+            }
+            Self::InvalidMin { bytes } | Self::InvalidMax { bytes } => {
+                let name = match self {
+                    Self::InvalidMin { .. } => "min",
+                    _ => "max",
+                };
+                let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+                write!(f, "{name} {hex} cannot be decoded")
+            }
+            Self::Unreadable { error } => write!(f, "cannot read the column chunk: {error}"),
+        }
+    }
+}
+
+/// Decode every column chunk and compare its min, max and null count with
+/// the statistics stored in the footer.
+pub(crate) fn audit_statistics<R: ChunkReader + 'static>(
+    reader: &Arc<R>,
+    metadata: &ParquetMetaData,
+) -> Result<Vec<ChunkStatisticsAudit>> {
+    let descr = metadata.file_metadata().schema_descr();
+    let props = Arc::new(ReaderProperties::builder().build());
+    let mut audits = Vec::new();
+    for (index, row_group) in metadata.row_groups().iter().enumerate() {
+        let row_group_reader =
+            SerializedRowGroupReader::new(reader.clone(), row_group, None, props.clone())?;
+        for column in 0..row_group.num_columns() {
+            let leaf = descr.column(column);
+            // A damaged chunk is reported on its own, the others are still read
+            let actual = match chunk_range(row_group.column(column)) {
+                Some(range) if range.end <= reader.len() => row_group_reader
+                    .get_column_reader(column)
+                    .map_err(Into::into)
+                    .and_then(|column_reader| recompute(column_reader, &leaf))
+                    .map_err(|error| error.to_string()),
+                _ => Err("the column chunk lies outside the file".to_string()),
+            };
+            audits.push(compare(
+                index,
+                &leaf,
+                row_group.column(column).statistics(),
+                actual,
+            ));
+        }
+    }
+    Ok(audits)
+}
+
+/// Min, max and null count of the values of a column chunk.
+#[derive(Default)]
+struct Recomputed {
+    min: Option<Bound>,
+    max: Option<Bound>,
+    null_count: u64,
+}
+
+impl Recomputed {
+    fn update(&mut self, value: Bound) {
+        if self
+            .min
+            .as_ref()
+            .is_none_or(|min| value.compare(min) == Ordering::Less)
+        {
+            self.min = Some(value.clone());
+        }
+        if self
+            .max
+            .as_ref()
+            .is_none_or(|max| value.compare(max) == Ordering::Greater)
+        {
+            self.max = Some(value);
+        }
+    }
+}
+
+fn recompute(reader: ColumnReader, column: &ColumnDescriptor) -> Result<Recomputed> {
+    let sort_order = column.sort_order();
+    let unsigned = sort_order == SortOrder::UNSIGNED;
+    let is_float16 = matches!(column.logical_type(), Some(LogicalType::Float16));
+    let max_def_level = column.max_def_level();
+
+    if sort_order == SortOrder::UNDEFINED {
+        return count_nulls(reader, max_def_level);
+    }
+    let recomputed = match reader {
+        ColumnReader::BoolColumnReader(r) => {
+            read_typed(r, max_def_level, |v| Some(Bound::Boolean(*v)))?
+        }
+        ColumnReader::Int32ColumnReader(r) if unsigned => read_typed(r, max_def_level, |v| {
+            Some(Bound::Unsigned(*v as u32 as u64))
+        })?,
+        ColumnReader::Int32ColumnReader(r) => {
+            read_typed(r, max_def_level, |v| Some(Bound::Signed(*v as i64)))?
+        }
+        ColumnReader::Int64ColumnReader(r) if unsigned => {
+            read_typed(r, max_def_level, |v| Some(Bound::Unsigned(*v as u64)))?
+        }
+        ColumnReader::Int64ColumnReader(r) => {
+            read_typed(r, max_def_level, |v| Some(Bound::Signed(*v)))?
+        }
+        ColumnReader::FloatColumnReader(r) => read_typed(r, max_def_level, |v| float(*v as f64))?,
+        ColumnReader::DoubleColumnReader(r) => read_typed(r, max_def_level, |v| float(*v))?,
+        ColumnReader::ByteArrayColumnReader(r) if unsigned => {
+            read_typed(r, max_def_level, |v: &ByteArray| {
+                Some(Bound::Bytes(v.data().to_vec()))
+            })?
+        }
+        ColumnReader::ByteArrayColumnReader(r) => read_typed(r, max_def_level, |v: &ByteArray| {
+            Some(Bound::SignedBytes(v.data().to_vec()))
+        })?,
+        ColumnReader::FixedLenByteArrayColumnReader(r) if is_float16 => {
+            read_typed(r, max_def_level, |v: &FixedLenByteArray| float(float16(v)?))?
+        }
+        ColumnReader::FixedLenByteArrayColumnReader(r) if unsigned => {
+            read_typed(r, max_def_level, |v: &FixedLenByteArray| {
+                Some(Bound::Bytes(v.data().to_vec()))
+            })?
+        }
+        ColumnReader::FixedLenByteArrayColumnReader(r) => {
+            read_typed(r, max_def_level, |v: &FixedLenByteArray| {
+                Some(Bound::SignedBytes(v.data().to_vec()))
+            })?
+        }
+        // INT96 timestamps have an undefined sort order
+        ColumnReader::Int96ColumnReader(r) => read_typed(r, max_def_level, |_| None)?,
+    };
+    Ok(recomputed)
+}
+
+/// Writers leave NaN out of statistics.
+fn float(value: f64) -> Option<Bound> {
+    (!value.is_nan()).then_some(Bound::Float(value))
+}
+
+fn read_typed<T: ParquetType>(
+    mut reader: ColumnReaderImpl<T>,
+    max_def_level: i16,
+    bound: impl Fn(&T::T) -> Option<Bound>,
+) -> Result<Recomputed> {
+    let mut recomputed = Recomputed::default();
+    let mut def_levels = Vec::new();
+    let mut rep_levels = Vec::new();
+    let mut values = Vec::new();
+    loop {
+        def_levels.clear();
+        rep_levels.clear();
+        values.clear();
+        let (records, _, levels_read) = reader.read_records(
+            BATCH_SIZE,
+            Some(&mut def_levels),
+            Some(&mut rep_levels),
+            &mut values,
+        )?;
+        if records == 0 && levels_read == 0 {
+            break;
+        }
+        if max_def_level > 0 {
+            recomputed.null_count += def_levels
+                .iter()
+                .filter(|level| **level < max_def_level)
+                .count() as u64;
+        }
+        for value in &values {
+            if let Some(value) = bound(value) {
+                recomputed.update(value);
+            }
+        }
+    }
+    Ok(recomputed)
+}
+
+fn compare(
+    row_group: usize,
+    column: &ColumnDescriptor,
+    statistics: Option<&Statistics>,
+    actual: std::result::Result<Recomputed, String>,
+) -> ChunkStatisticsAudit {
+    let render = |bound: &Option<Bound>| bound.as_ref().map(Bound::render);
+    let (actual, error) = match actual {
+        Ok(actual) => (actual, None),
+        Err(error) => (Recomputed::default(), Some(error)),
+    };
+    let mut audit = ChunkStatisticsAudit {
+        row_group,
+        column: column.path().string(),
+        has_statistics: statistics.is_some(),
+        deprecated_min_max: statistics.is_some_and(|s| s.is_min_max_deprecated()),
+        stored_min: None,
+        stored_max: None,
+        stored_null_count: statistics.and_then(|s| s.null_count_opt()),
+        actual_min: render(&actual.min),
+        actual_max: render(&actual.max),
+        actual_null_count: actual.null_count,
+        mismatches: Vec::new(),
+    };
+    let Some(statistics) = statistics else {
+        audit
+            .mismatches
+            .extend(error.map(|error| StatisticsMismatch::Unreadable { error }));
+        return audit;
+    };
+
+    let is_float16 = matches!(column.logical_type(), Some(LogicalType::Float16));
+    let (stored_min, stored_max) = bounds(statistics, column.sort_order(), is_float16);
+    audit.stored_min = render(&stored_min);
+    audit.stored_max = render(&stored_max);
+    if let Some(error) = error {
+        audit
+            .mismatches
+            .push(StatisticsMismatch::Unreadable { error });
+        return audit;
+    }
+
+    // Stored bounds that are present but do not decode
+    if column.sort_order() != SortOrder::UNDEFINED {
+        if let Some(bytes) = statistics.min_bytes_opt().filter(|_| stored_min.is_none()) {
+            audit.mismatches.push(StatisticsMismatch::InvalidMin {
+                bytes: bytes.to_vec(),
+            });
+        }
+        if let Some(bytes) = statistics.max_bytes_opt().filter(|_| stored_max.is_none()) {
+            audit.mismatches.push(StatisticsMismatch::InvalidMax {
+                bytes: bytes.to_vec(),
+            });
+        }
+    }
+
+    if let Some(stored) = stored_min {
+        let exact = statistics.min_is_exact();
+        if !agrees(&stored, actual.min.as_ref(), exact, Ordering::Greater) {
+            audit.mismatches.push(StatisticsMismatch::Min {
+                stored: stored.render(),
+                actual: audit.actual_min.clone(),
+                exact,
+            });
+        }
+    }
+    if let Some(stored) = stored_max {
+        let exact = statistics.max_is_exact();
+        if !agrees(&stored, actual.max.as_ref(), exact, Ordering::Less) {
+            audit.mismatches.push(StatisticsMismatch::Max {
+                stored: stored.render(),
+                actual: audit.actual_max.clone(),
+                exact,
+            });
+        }
+    }
+    if let Some(stored) = audit.stored_null_count
+        && stored != actual.null_count
+    {
+        audit.mismatches.push(StatisticsMismatch::NullCount {
+            stored,
+            actual: actual.null_count,
+        });
+    }
+    audit
+}
+
+/// Whether a stored bound matches the actual one, or for truncated bounds,
+/// is not on the wrong side (`beyond`) of it.
+fn agrees(stored: &Bound, actual: Option<&Bound>, exact: bool, beyond: Ordering) -> bool {
+    let Some(actual) = actual else {
+        return false;
+    };
+    // Writers store -0 as min and +0 as max of zeros
+    if let (Bound::Float(stored), Bound::Float(actual)) = (stored, actual)
+        && stored == actual
+    {
+        return true;
+    }
+    match stored.compare(actual) {
+        Ordering::Equal => true,
+        ordering => !exact && ordering != beyond,
+    }
+}
+
+/// Count the nulls of a column whose values have no defined order.
+fn count_nulls(reader: ColumnReader, max_def_level: i16) -> Result<Recomputed> {
+    match reader {
+        ColumnReader::BoolColumnReader(r) => read_typed(r, max_def_level, |_| None),
+        ColumnReader::Int32ColumnReader(r) => read_typed(r, max_def_level, |_| None),
+        ColumnReader::Int64ColumnReader(r) => read_typed(r, max_def_level, |_| None),
+        ColumnReader::Int96ColumnReader(r) => read_typed(r, max_def_level, |_| None),
+        ColumnReader::FloatColumnReader(r) => read_typed(r, max_def_level, |_| None),
+        ColumnReader::DoubleColumnReader(r) => read_typed(r, max_def_level, |_| None),
+        ColumnReader::ByteArrayColumnReader(r) => read_typed(r, max_def_level, |_| None),
+        ColumnReader::FixedLenByteArrayColumnReader(r) => read_typed(r, max_def_level, |_| None),
+    }
+}
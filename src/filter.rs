@@ -0,0 +1,639 @@
+use crate::projection::unknown_column;
+use crate::{ParquetViewerError, Result};
+use arrow::array::{
+    Array, ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, Scalar, StringArray,
+    UInt64Array,
+};
+use arrow::buffer::BooleanBuffer;
+use arrow::compute::kernels::cmp;
+use arrow::compute::{
+    and_kleene, cast, is_not_null, is_null, not, or_kleene, prep_null_mask_filter,
+};
+use arrow::datatypes::DataType;
+use arrow_schema::Schema;
+use parquet::arrow::arrow_reader::statistics::StatisticsConverter;
+use parquet::arrow::arrow_reader::{RowSelection, RowSelector};
+use parquet::file::metadata::{ParquetColumnIndex, ParquetMetaData, ParquetOffsetIndex};
+use sqlparser::ast::{BinaryOperator, Expr, UnaryOperator, Value};
+use sqlparser::dialect::PostgreSqlDialect;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::Token;
+use std::sync::Arc;
+
+/// Comparison operator of a [`Filter::Compare`] predicate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+}
+
+impl CompareOp {
+    /// The operator that gives the same result with the operands swapped.
+    fn flip(self) -> Self {
+        match self {
+            CompareOp::Eq => CompareOp::Eq,
+            CompareOp::NotEq => CompareOp::NotEq,
+            CompareOp::Lt => CompareOp::Gt,
+            CompareOp::LtEq => CompareOp::GtEq,
+            CompareOp::Gt => CompareOp::Lt,
+            CompareOp::GtEq => CompareOp::LtEq,
+        }
+    }
+}
+
+/// Literal value a column is compared against.
+///
+/// Literals are cast to the type of the column they are compared with, or
+/// integers to 64-bit integers for integer columns.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Boolean(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+/// Row filter over top-level columns.
+///
+/// Parquet row groups that cannot match are skipped using the min, max and
+/// null count statistics of their column chunks, and the remaining rows are
+/// filtered while decoding.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    Compare {
+        column: String,
+        op: CompareOp,
+        value: Literal,
+    },
+    InList {
+        column: String,
+        values: Vec<Literal>,
+        negated: bool,
+    },
+    IsNull {
+        column: String,
+        negated: bool,
+    },
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+}
+
+impl Filter {
+    /// Parse a SQL `WHERE` style expression, e.g. `status = 412 AND host IS NOT NULL`.
+    pub fn parse(expr: &str) -> Result<Self> {
+        let dialect = PostgreSqlDialect {};
+        let mut parser = Parser::new(&dialect).try_with_sql(expr)?;
+        let ast = parser.parse_expr()?;
+
+        let token = parser.peek_token();
+        if token.token != Token::EOF {
+            return Err(ParquetViewerError::InvalidFilter(format!(
+                "Unexpected '{}' after expression",
+                token.token
+            )));
+        }
+
+        Self::from_sql(&ast)
+    }
+
+    fn from_sql(expr: &Expr) -> Result<Self> {
+        match expr {
+            Expr::Nested(inner) => Self::from_sql(inner),
+            Expr::BinaryOp {
+                left,
+                op: BinaryOperator::And,
+                right,
+            } => Ok(Filter::And(
+                Box::new(Self::from_sql(left)?),
+                Box::new(Self::from_sql(right)?),
+            )),
+            Expr::BinaryOp {
+                left,
+                op: BinaryOperator::Or,
+                right,
+            } => Ok(Filter::Or(
+                Box::new(Self::from_sql(left)?),
+                Box::new(Self::from_sql(right)?),
+            )),
+            Expr::BinaryOp { left, op, right } => {
+                let op = match op {
+                    BinaryOperator::Eq => CompareOp::Eq,
+                    BinaryOperator::NotEq => CompareOp::NotEq,
+                    BinaryOperator::Lt => CompareOp::Lt,
+                    BinaryOperator::LtEq => CompareOp::LtEq,
+                    BinaryOperator::Gt => CompareOp::Gt,
+                    BinaryOperator::GtEq => CompareOp::GtEq,
+                    _ => return Err(unsupported(expr)),
+                };
+
+                // Accept both `column op value` and `value op column`
+                if let Some(column) = column_name(left) {
+                    let value = literal(right)?.ok_or_else(|| unsupported(expr))?;
+                    Ok(Filter::Compare { column, op, value })
+                } else if let Some(column) = column_name(right) {
+                    let value = literal(left)?.ok_or_else(|| unsupported(expr))?;
+                    Ok(Filter::Compare {
+                        column,
+                        op: op.flip(),
+                        value,
+                    })
+                } else {
+                    Err(unsupported(expr))
+                }
+            }
+            Expr::InList {
+                expr: column,
+                list,
+                negated,
+            } => {
+                let column = column_name(column).ok_or_else(|| unsupported(expr))?;
+                let values = list
+                    .iter()
+                    .map(|item| literal(item)?.ok_or_else(|| unsupported(item)))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(Filter::InList {
+                    column,
+                    values,
+                    negated: *negated,
+                })
+            }
+            Expr::IsNull(column) | Expr::IsNotNull(column) => Ok(Filter::IsNull {
+                column: column_name(column).ok_or_else(|| unsupported(expr))?,
+                negated: matches!(expr, Expr::IsNotNull(_)),
+            }),
+            _ => Err(unsupported(expr)),
+        }
+    }
+
+    /// Names of the columns referenced by this filter, without duplicates.
+    pub fn columns(&self) -> Vec<&str> {
+        let mut columns = Vec::new();
+        self.collect_columns(&mut columns);
+        columns
+    }
+
+    fn collect_columns<'a>(&'a self, columns: &mut Vec<&'a str>) {
+        match self {
+            Filter::Compare { column, .. }
+            | Filter::InList { column, .. }
+            | Filter::IsNull { column, .. } => {
+                if !columns.contains(&column.as_str()) {
+                    columns.push(column);
+                }
+            }
+            Filter::And(left, right) | Filter::Or(left, right) => {
+                left.collect_columns(columns);
+                right.collect_columns(columns);
+            }
+        }
+    }
+
+    /// Root column indices of the referenced columns in `schema`.
+    pub(crate) fn column_indices(&self, schema: &Schema) -> Result<Vec<usize>> {
+        let mut indices = self
+            .columns()
+            .into_iter()
+            .map(|column| {
+                schema
+                    .index_of(column)
+                    .map_err(|_| unknown_column(schema, column))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        indices.sort_unstable();
+        Ok(indices)
+    }
+
+    /// Evaluate the filter against a batch. Rows where the result is null,
+    /// e.g. comparisons against null values, are not selected.
+    pub fn evaluate(&self, batch: &RecordBatch) -> Result<BooleanArray> {
+        let mask = self.evaluate_kleene(batch)?;
+        if mask.null_count() == 0 {
+            return Ok(mask);
+        }
+        Ok(prep_null_mask_filter(&mask))
+    }
+
+    fn evaluate_kleene(&self, batch: &RecordBatch) -> Result<BooleanArray> {
+        match self {
+            Filter::Compare { column, op, value } => {
+                compare(column_array(batch, column)?, *op, value)
+            }
+            Filter::InList {
+                column,
+                values,
+                negated,
+            } => {
+                let array = column_array(batch, column)?;
+                let mut result = BooleanArray::from(vec![false; array.len()]);
+                for value in values {
+                    result = or_kleene(&result, &compare(array, CompareOp::Eq, value)?)?;
+                }
+                if *negated {
+                    result = not(&result)?;
+                }
+                Ok(result)
+            }
+            Filter::IsNull { column, negated } => {
+                let array = column_array(batch, column)?;
+                Ok(if *negated {
+                    is_not_null(array)?
+                } else {
+                    is_null(array)?
+                })
+            }
+            Filter::And(left, right) => Ok(and_kleene(
+                &left.evaluate_kleene(batch)?,
+                &right.evaluate_kleene(batch)?,
+            )?),
+            Filter::Or(left, right) => Ok(or_kleene(
+                &left.evaluate_kleene(batch)?,
+                &right.evaluate_kleene(batch)?,
+            )?),
+        }
+    }
+
+    /// Indices of the row groups that may contain matching rows, judged from
+    /// the column chunk statistics in the footer.
+    ///
+    /// Row groups without usable statistics are always kept.
+    pub fn prune_row_groups(
+        &self,
+        metadata: &ParquetMetaData,
+        schema: &Schema,
+    ) -> Result<Vec<usize>> {
+        let may_match =
+            self.may_match(&|column| ColumnStatistics::row_groups(column, metadata, schema))?;
+        Ok((0..metadata.num_row_groups())
+            .filter(|&i| may_match.value(i))
+            .collect())
+    }
+
+    /// Rows of the given row groups that may match, judged from the page
+    /// index. Pages whose statistics rule out the filter are skipped.
+    ///
+    /// Returns `None` when the file has no page index, in which case every
+    /// row of the row groups has to be read.
+    pub fn select_pages(
+        &self,
+        metadata: &ParquetMetaData,
+        schema: &Schema,
+        row_groups: &[usize],
+    ) -> Result<Option<RowSelection>> {
+        let (Some(column_index), Some(offset_index)) =
+            (metadata.column_index(), metadata.offset_index())
+        else {
+            return Ok(None);
+        };
+
+        let mut selectors = Vec::new();
+        for &row_group in row_groups {
+            let index = PageIndex {
+                metadata,
+                column_index,
+                offset_index,
+                row_group,
+            };
+            match self.page_selection(schema, &index)? {
+                Some(selection) => selectors.extend(selection.iter().copied()),
+                None => selectors.push(RowSelector::select(
+                    metadata.row_group(row_group).num_rows() as usize,
+                )),
+            }
+        }
+        Ok(Some(selectors.into()))
+    }
+
+    /// Rows of one row group that may match, `None` when every row may.
+    ///
+    /// Pages of different columns do not line up, so each predicate is
+    /// turned into a row selection before they are combined.
+    fn page_selection(&self, schema: &Schema, index: &PageIndex) -> Result<Option<RowSelection>> {
+        match self {
+            Filter::And(left, right) => {
+                let left = left.page_selection(schema, index)?;
+                let right = right.page_selection(schema, index)?;
+                Ok(match (left, right) {
+                    (Some(left), Some(right)) => Some(left.intersection(&right)),
+                    (selection, None) | (None, selection) => selection,
+                })
+            }
+            Filter::Or(left, right) => {
+                let left = left.page_selection(schema, index)?;
+                let right = right.page_selection(schema, index)?;
+                Ok(match (left, right) {
+                    (Some(left), Some(right)) => Some(left.union(&right)),
+                    _ => None,
+                })
+            }
+            _ => {
+                let stats = |column: &str| ColumnStatistics::pages(column, schema, index);
+                let may_match = self.may_match(&stats)?;
+                let Some(column) = self.columns().first().copied() else {
+                    return Ok(None);
+                };
+                let row_counts = stats(column)?.row_counts;
+                if row_counts.null_count() > 0 {
+                    return Ok(None);
+                }
+
+                let selectors: Vec<_> = row_counts
+                    .values()
+                    .iter()
+                    .zip(may_match.iter())
+                    .map(|(&rows, matches)| {
+                        if matches == Some(false) {
+                            RowSelector::skip(rows as usize)
+                        } else {
+                            RowSelector::select(rows as usize)
+                        }
+                    })
+                    .collect();
+                Ok(Some(selectors.into()))
+            }
+        }
+    }
+
+    /// One entry per row group or page, `false` only when it certainly
+    /// contains no matching row.
+    fn may_match(&self, stats: &dyn Fn(&str) -> Result<ColumnStatistics>) -> Result<BooleanArray> {
+        match self {
+            Filter::Compare { column, op, value } => stats(column)?.may_match(*op, value),
+            Filter::InList {
+                column,
+                values,
+                negated: false,
+            } => {
+                let stats = stats(column)?;
+                let mut result = BooleanArray::from(vec![false; stats.len()]);
+                for value in values {
+                    result = or_kleene(&result, &stats.may_match(CompareOp::Eq, value)?)?;
+                }
+                Ok(result)
+            }
+            Filter::InList {
+                column,
+                negated: true,
+                ..
+            } => Ok(stats(column)?.unknown()),
+            Filter::IsNull { column, negated } => {
+                let stats = stats(column)?;
+                let result = if *negated {
+                    cmp::lt(&stats.null_counts, &stats.row_counts)?
+                } else {
+                    cmp::gt(&stats.null_counts, &Scalar::new(UInt64Array::from(vec![0])))?
+                };
+                Ok(unknown_as_match(&result))
+            }
+            Filter::And(left, right) => Ok(and_kleene(
+                &left.may_match(stats)?,
+                &right.may_match(stats)?,
+            )?),
+            Filter::Or(left, right) => Ok(or_kleene(
+                &left.may_match(stats)?,
+                &right.may_match(stats)?,
+            )?),
+        }
+    }
+}
+
+/// Page index of one row group.
+struct PageIndex<'a> {
+    metadata: &'a ParquetMetaData,
+    column_index: &'a ParquetColumnIndex,
+    offset_index: &'a ParquetOffsetIndex,
+    row_group: usize,
+}
+
+/// Statistics of one column, with one entry per row group or per page.
+struct ColumnStatistics {
+    /// `None` when the column has no comparable min/max statistics.
+    mins: Option<ArrayRef>,
+    maxes: Option<ArrayRef>,
+    null_counts: UInt64Array,
+    row_counts: UInt64Array,
+}
+
+impl ColumnStatistics {
+    /// Statistics of every row group, read from the column chunks in the
+    /// footer.
+    fn row_groups(column: &str, metadata: &ParquetMetaData, schema: &Schema) -> Result<Self> {
+        let converter = statistics_converter(column, metadata, schema)?;
+        let row_groups = metadata.row_groups();
+
+        let row_counts = converter
+            .row_group_row_counts(row_groups)?
+            .unwrap_or_else(|| UInt64Array::new_null(row_groups.len()));
+
+        Ok(Self::new(
+            converter.parquet_column_index().is_some(),
+            converter.row_group_mins(row_groups)?,
+            converter.row_group_maxes(row_groups)?,
+            converter.row_group_null_counts(row_groups)?,
+            row_counts,
+        ))
+    }
+
+    /// Statistics of every data page of a row group, read from the page index.
+    fn pages(column: &str, schema: &Schema, index: &PageIndex) -> Result<Self> {
+        let converter = statistics_converter(column, index.metadata, schema)?;
+        let row_groups = [index.row_group];
+
+        let null_counts =
+            converter.data_page_null_counts(index.column_index, index.offset_index, &row_groups)?;
+        let row_counts = converter
+            .data_page_row_counts(index.offset_index, index.metadata.row_groups(), &row_groups)?
+            .unwrap_or_else(|| UInt64Array::new_null(null_counts.len()));
+
+        Ok(Self::new(
+            converter.parquet_column_index().is_some(),
+            converter.data_page_mins(index.column_index, index.offset_index, &row_groups)?,
+            converter.data_page_maxes(index.column_index, index.offset_index, &row_groups)?,
+            null_counts,
+            row_counts,
+        ))
+    }
+
+    fn new(
+        is_leaf: bool,
+        mins: ArrayRef,
+        maxes: ArrayRef,
+        null_counts: UInt64Array,
+        row_counts: UInt64Array,
+    ) -> Self {
+        let has_min_max =
+            is_leaf && !mins.data_type().is_nested() && mins.null_count() < mins.len();
+        Self {
+            mins: has_min_max.then_some(mins),
+            maxes: has_min_max.then_some(maxes),
+            null_counts,
+            row_counts,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.null_counts.len()
+    }
+
+    fn unknown(&self) -> BooleanArray {
+        BooleanArray::from(vec![true; self.len()])
+    }
+
+    /// Whether each entry may hold a value for which `value op literal` is true.
+    fn may_match(&self, op: CompareOp, value: &Literal) -> Result<BooleanArray> {
+        let (Some(mins), Some(maxes)) = (&self.mins, &self.maxes) else {
+            return Ok(self.unknown());
+        };
+
+        let result = match op {
+            CompareOp::Eq => and_kleene(
+                &compare(mins, CompareOp::LtEq, value)?,
+                &compare(maxes, CompareOp::GtEq, value)?,
+            )?,
+            // Only entries holding nothing but the value can be skipped
+            CompareOp::NotEq => not(&and_kleene(
+                &compare(mins, CompareOp::Eq, value)?,
+                &compare(maxes, CompareOp::Eq, value)?,
+            )?)?,
+            CompareOp::Lt | CompareOp::LtEq => compare(mins, op, value)?,
+            CompareOp::Gt | CompareOp::GtEq => compare(maxes, op, value)?,
+        };
+        Ok(unknown_as_match(&result))
+    }
+}
+
+fn statistics_converter<'a>(
+    column: &str,
+    metadata: &'a ParquetMetaData,
+    schema: &'a Schema,
+) -> Result<StatisticsConverter<'a>> {
+    if schema.index_of(column).is_err() {
+        return Err(unknown_column(schema, column));
+    }
+
+    // Missing null counts must not be read as zero, or IS NULL would skip
+    // row groups written without them
+    Ok(
+        StatisticsConverter::try_new(column, schema, metadata.file_metadata().schema_descr())?
+            .with_missing_null_counts_as_zero(false),
+    )
+}
+
+/// Treat row groups and pages whose statistics could not decide the
+/// predicate as possible matches.
+fn unknown_as_match(result: &BooleanArray) -> BooleanArray {
+    result
+        .iter()
+        .map(|value| Some(value.unwrap_or(true)))
+        .collect()
+}
+
+fn column_array<'a>(batch: &'a RecordBatch, column: &str) -> Result<&'a ArrayRef> {
+    batch
+        .column_by_name(column)
+        .ok_or_else(|| unknown_column(&batch.schema(), column))
+}
+
+/// Compare every value of `array` with a literal.
+fn compare(array: &ArrayRef, op: CompareOp, value: &Literal) -> Result<BooleanArray> {
+    let literal: ArrayRef = match value {
+        Literal::Boolean(value) => Arc::new(BooleanArray::from(vec![*value])),
+        Literal::Int(value) => Arc::new(Int64Array::from(vec![*value])),
+        Literal::Float(value) => Arc::new(Float64Array::from(vec![*value])),
+        Literal::String(value) => Arc::new(StringArray::from(vec![value.as_str()])),
+    };
+
+    // Compare integer columns against fractional literals as floats, instead
+    // of truncating the literal, and against integer literals as 64-bit
+    // integers, as literals out of the range of the column cast to null
+    let data_type = array.data_type();
+    let (array, literal) = match value {
+        Literal::Float(_) if data_type.is_integer() => (cast(array, &DataType::Float64)?, literal),
+        // Below every unsigned value
+        Literal::Int(value) if *value < 0 && data_type.is_unsigned_integer() => {
+            let result = matches!(op, CompareOp::NotEq | CompareOp::Gt | CompareOp::GtEq);
+            return Ok(BooleanArray::new(
+                BooleanBuffer::collect_bool(array.len(), |_| result),
+                array.logical_nulls(),
+            ));
+        }
+        Literal::Int(_) if data_type.is_unsigned_integer() => (
+            cast(array, &DataType::UInt64)?,
+            cast(&literal, &DataType::UInt64)?,
+        ),
+        Literal::Int(_) if data_type.is_signed_integer() => {
+            (cast(array, &DataType::Int64)?, literal)
+        }
+        _ => (array.clone(), cast(&literal, data_type)?),
+    };
+    let literal = Scalar::new(literal);
+
+    let result = match op {
+        CompareOp::Eq => cmp::eq(&array, &literal)?,
+        CompareOp::NotEq => cmp::neq(&array, &literal)?,
+        CompareOp::Lt => cmp::lt(&array, &literal)?,
+        CompareOp::LtEq => cmp::lt_eq(&array, &literal)?,
+        CompareOp::Gt => cmp::gt(&array, &literal)?,
+        CompareOp::GtEq => cmp::gt_eq(&array, &literal)?,
+    };
+    Ok(result)
+}
+
+fn column_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Identifier(ident) => Some(ident.value.clone()),
+        Expr::CompoundIdentifier(idents) => Some(
+            idents
+                .iter()
+                .map(|ident| ident.value.as_str())
+                .collect::<Vec<_>>()
+                .join("."),
+        ),
+        Expr::Nested(inner) => column_name(inner),
+        _ => None,
+    }
+}
+
+fn literal(expr: &Expr) -> Result<Option<Literal>> {
+    let value = match expr {
+        Expr::Value(value) => match &value.value {
+            Value::Number(number, _) => number_literal(number)?,
+            Value::SingleQuotedString(value) => Literal::String(value.clone()),
+            Value::Boolean(value) => Literal::Boolean(*value),
+            Value::Null => {
+                return Err(ParquetViewerError::InvalidFilter(
+                    "Comparisons with NULL never match, use IS NULL instead".to_string(),
+                ));
+            }
+            _ => return Ok(None),
+        },
+        Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr,
+        } => match literal(expr)? {
+            Some(Literal::Int(value)) => Literal::Int(-value),
+            Some(Literal::Float(value)) => Literal::Float(-value),
+            _ => return Ok(None),
+        },
+        Expr::Nested(inner) => return literal(inner),
+        _ => return Ok(None),
+    };
+    Ok(Some(value))
+}
+
+fn number_literal(number: &str) -> Result<Literal> {
+    if let Ok(value) = number.parse::<i64>() {
+        return Ok(Literal::Int(value));
+    }
+    number
+        .parse::<f64>()
+        .map(Literal::Float)
+        .map_err(|_| ParquetViewerError::InvalidFilter(format!("Invalid number '{number}'")))
+}
+
+fn unsupported(expr: &Expr) -> ParquetViewerError {
+    ParquetViewerError::InvalidFilter(format!("Unsupported filter expression: {expr}"))
+}
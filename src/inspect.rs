@@ -0,0 +1,381 @@
+use crate::projection::similar_names;
+use crate::{ChunkStatistics, ParquetViewerError, Result, chunk_statistics, format_bytes};
+use parquet::column::page::Page;
+use parquet::column::reader::{ColumnReader, ColumnReaderImpl};
+use parquet::data_type::DataType as ParquetType;
+use parquet::errors::ParquetError;
+use parquet::file::metadata::{ColumnChunkMetaData, ParquetMetaData};
+use parquet::file::properties::ReaderProperties;
+use parquet::file::reader::{ChunkReader, RowGroupReader};
+use parquet::file::serialized_reader::SerializedRowGroupReader;
+use parquet::format::{PageHeader, PageType};
+use parquet::thrift::TSerializable;
+use std::ops::Range;
+use std::sync::Arc;
+use thrift::protocol::TCompactInputProtocol;
+
+/// A Parquet leaf column with the maximum levels of its values.
+#[derive(Debug, Clone)]
+pub struct LeafColumn {
+    /// Dotted path of the leaf column.
+    pub path: String,
+    pub physical_type: String,
+    /// Definition level of a non-null value, the number of optional or
+    /// repeated fields on its path.
+    pub max_def_level: i16,
+    /// Number of repeated fields on the path of the column.
+    pub max_rep_level: i16,
+}
+
+/// A value of a leaf column as stored, with its repetition and definition
+/// levels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelValue {
+    pub row_group: usize,
+    /// Zero when the value starts a new row.
+    pub rep_level: i16,
+    pub def_level: i16,
+    /// The physical value, `None` when the definition level is below the
+    /// maximum, i.e. when the value or one of its parents is null or empty.
+    pub value: Option<String>,
+}
+
+/// Header and statistics of one page of a column chunk.
+#[derive(Debug, Clone)]
+pub struct PageDetails {
+    pub row_group: usize,
+    /// File offset of the page header.
+    pub offset: u64,
+    /// `DICTIONARY_PAGE`, `DATA_PAGE` or `DATA_PAGE_V2`.
+    pub page_type: String,
+    pub encoding: String,
+    /// Number of values, including nulls.
+    pub num_values: u32,
+    /// Rows and nulls in the page, only recorded by data pages v2.
+    pub num_rows: Option<u32>,
+    pub num_nulls: Option<u32>,
+    pub header_size: usize,
+    /// Size of the page after the header, as stored.
+    pub compressed_size: usize,
+    pub uncompressed_size: usize,
+    /// CRC32 checksum of the stored page, if the writer recorded one.
+    pub crc: Option<u32>,
+    pub statistics: Option<ChunkStatistics>,
+}
+
+pub(crate) fn leaf_columns(metadata: &ParquetMetaData) -> Vec<LeafColumn> {
+    let descr = metadata.file_metadata().schema_descr();
+    descr
+        .columns()
+        .iter()
+        .map(|column| LeafColumn {
+            path: column.path().string(),
+            physical_type: column.physical_type().to_string(),
+            max_def_level: column.max_def_level(),
+            max_rep_level: column.max_rep_level(),
+        })
+        .collect()
+}
+
+/// Position of a leaf column given its dotted path.
+pub(crate) fn leaf_index(metadata: &ParquetMetaData, path: &str) -> Result<usize> {
+    let descr = metadata.file_metadata().schema_descr();
+    let paths: Vec<_> = descr.columns().iter().map(|c| c.path().string()).collect();
+    paths
+        .iter()
+        .position(|candidate| candidate == path)
+        .ok_or_else(|| ParquetViewerError::UnknownColumn {
+            name: path.to_string(),
+            suggestions: similar_names(path, paths.clone()),
+        })
+}
+
+/// Read the first `limit` levels of a leaf column with the low-level column
+/// reader, from one row group or from the start of the file.
+pub(crate) fn read_levels<R: ChunkReader + 'static>(
+    reader: &Arc<R>,
+    metadata: &ParquetMetaData,
+    path: &str,
+    row_group: Option<usize>,
+    limit: usize,
+) -> Result<Vec<LevelValue>> {
+    let column = leaf_index(metadata, path)?;
+    let max_def_level = metadata
+        .file_metadata()
+        .schema_descr()
+        .column(column)
+        .max_def_level();
+    let row_groups = match row_group {
+        Some(index) if index >= metadata.num_row_groups() => {
+            return Err(ParquetViewerError::RowGroupOutOfRange {
+                index,
+                num_row_groups: metadata.num_row_groups(),
+            });
+        }
+        Some(index) => index..index + 1,
+        None => 0..metadata.num_row_groups(),
+    };
+
+    let props = Arc::new(ReaderProperties::builder().build());
+    let mut levels = Vec::new();
+    for index in row_groups {
+        if levels.len() >= limit {
+            break;
+        }
+        chunk_range_in_file(reader.as_ref(), metadata, index, column)?;
+        let row_group = SerializedRowGroupReader::new(
+            reader.clone(),
+            metadata.row_group(index),
+            None,
+            props.clone(),
+        )?;
+        let mut out = LevelReader {
+            row_group: index,
+            max_def_level,
+            limit,
+            levels: &mut levels,
+        };
+        match row_group.get_column_reader(column)? {
+            ColumnReader::BoolColumnReader(r) => out.read(r, |v| v.to_string())?,
+            ColumnReader::Int32ColumnReader(r) => out.read(r, |v| v.to_string())?,
+            ColumnReader::Int64ColumnReader(r) => out.read(r, |v| v.to_string())?,
+            ColumnReader::Int96ColumnReader(r) => out.read(r, |v| v.to_string())?,
+            ColumnReader::FloatColumnReader(r) => out.read(r, |v| v.to_string())?,
+            ColumnReader::DoubleColumnReader(r) => out.read(r, |v| v.to_string())?,
+            ColumnReader::ByteArrayColumnReader(r) => out.read(r, |v| format_bytes(v.data()))?,
+            ColumnReader::FixedLenByteArrayColumnReader(r) => {
+                out.read(r, |v| format_bytes(v.data()))?
+            }
+        }
+    }
+    Ok(levels)
+}
+
+/// Collects the levels of one column chunk.
+struct LevelReader<'a> {
+    row_group: usize,
+    max_def_level: i16,
+    limit: usize,
+    levels: &'a mut Vec<LevelValue>,
+}
+
+impl LevelReader<'_> {
+    fn read<T: ParquetType>(
+        &mut self,
+        mut reader: ColumnReaderImpl<T>,
+        format: impl Fn(&T::T) -> String,
+    ) -> Result<()> {
+        let mut def_levels = Vec::new();
+        let mut rep_levels = Vec::new();
+        let mut values = Vec::new();
+
+        while self.levels.len() < self.limit {
+            def_levels.clear();
+            rep_levels.clear();
+            values.clear();
+            let (records, _, levels_read) = reader.read_records(
+                self.limit - self.levels.len(),
+                Some(&mut def_levels),
+                Some(&mut rep_levels),
+                &mut values,
+            )?;
+            if records == 0 && levels_read == 0 {
+                break;
+            }
+
+            // Levels are not stored for required columns outside of lists
+            let num_levels = if def_levels.is_empty() {
+                values.len()
+            } else {
+                def_levels.len()
+            };
+            let mut values = values.iter();
+            for i in 0..num_levels {
+                let def_level = def_levels.get(i).copied().unwrap_or(self.max_def_level);
+                let value = if def_level == self.max_def_level {
+                    values.next().map(&format)
+                } else {
+                    None
+                };
+                self.levels.push(LevelValue {
+                    row_group: self.row_group,
+                    rep_level: rep_levels.get(i).copied().unwrap_or(0),
+                    def_level,
+                    value,
+                });
+            }
+        }
+        self.levels.truncate(self.limit);
+        Ok(())
+    }
+}
+
+/// A page header and where its page is stored, relative to the start of the
+/// bytes it was read from.
+pub(crate) struct RawPage {
+    pub offset: usize,
+    pub header: PageHeader,
+    /// Stored page data, following the header.
+    pub data: Range<usize>,
+}
+
+/// Decode a page header at the start of `bytes`, returning it with its
+/// encoded length.
+pub(crate) fn parse_page_header(bytes: &[u8]) -> Result<(PageHeader, usize)> {
+    let mut input = bytes;
+    let mut protocol = TCompactInputProtocol::new(&mut input);
+    let header = PageHeader::read_from_in_protocol(&mut protocol).map_err(ParquetError::from)?;
+    Ok((header, bytes.len() - input.len()))
+}
+
+/// Read the header of the page starting at `offset` in `bytes`.
+pub(crate) fn read_page_header(bytes: &[u8], offset: usize) -> Result<RawPage> {
+    let (header, header_size) = parse_page_header(&bytes[offset..])?;
+    let start = offset + header_size;
+    let end = usize::try_from(header.compressed_page_size)
+        .ok()
+        .and_then(|size| start.checked_add(size))
+        .filter(|end| *end <= bytes.len())
+        .ok_or_else(|| {
+            ParquetError::EOF(format!(
+                "Page at offset {offset} of {} bytes extends past the end of its column chunk",
+                header.compressed_page_size
+            ))
+        })?;
+    Ok(RawPage {
+        offset,
+        header,
+        data: start..end,
+    })
+}
+
+/// Byte range of a column chunk from the raw footer fields, `None` when
+/// they are negative or overflow.
+pub(crate) fn chunk_range(chunk: &ColumnChunkMetaData) -> Option<Range<u64>> {
+    let start = chunk
+        .dictionary_page_offset()
+        .unwrap_or(chunk.data_page_offset());
+    let start = u64::try_from(start).ok()?;
+    let length = u64::try_from(chunk.compressed_size()).ok()?;
+    Some(start..start.checked_add(length)?)
+}
+
+/// Byte range of the chunk of leaf `column` in row group `index`, or an
+/// error naming both when it does not lie within the file.
+fn chunk_range_in_file<R: ChunkReader>(
+    reader: &R,
+    metadata: &ParquetMetaData,
+    index: usize,
+    column: usize,
+) -> Result<Range<u64>> {
+    let chunk = metadata.row_group(index).column(column);
+    let range = chunk_range(chunk)
+        .filter(|range| range.end <= reader.len())
+        .ok_or_else(|| {
+            ParquetError::General(format!(
+                "Column chunk {} of row group {index} lies outside the file",
+                chunk.column_path().string()
+            ))
+        })?;
+    Ok(range)
+}
+
+/// Read every page header of a column chunk.
+pub(crate) fn read_page_headers(bytes: &[u8]) -> Result<Vec<RawPage>> {
+    let mut pages = Vec::new();
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let page = read_page_header(bytes, offset)?;
+        offset = page.data.end;
+        pages.push(page);
+    }
+    Ok(pages)
+}
+
+/// Read the pages of a leaf column chunk in every row group, or in one.
+///
+/// Sizes and checksums come from the raw page headers, encodings and
+/// statistics from the pages decoded by the column page reader.
+pub(crate) fn read_pages<R: ChunkReader + 'static>(
+    reader: &Arc<R>,
+    metadata: &ParquetMetaData,
+    path: &str,
+    row_group: Option<usize>,
+) -> Result<Vec<PageDetails>> {
+    let column = leaf_index(metadata, path)?;
+    let row_groups = match row_group {
+        Some(index) if index >= metadata.num_row_groups() => {
+            return Err(ParquetViewerError::RowGroupOutOfRange {
+                index,
+                num_row_groups: metadata.num_row_groups(),
+            });
+        }
+        Some(index) => index..index + 1,
+        None => 0..metadata.num_row_groups(),
+    };
+
+    let props = Arc::new(ReaderProperties::builder().build());
+    let mut details = Vec::new();
+    for index in row_groups {
+        let range = chunk_range_in_file(reader.as_ref(), metadata, index, column)?;
+        let start = range.start;
+        let bytes = reader.get_bytes(start, (range.end - start) as usize)?;
+        // The page reader skips index pages
+        let headers: Vec<_> = read_page_headers(&bytes)?
+            .into_iter()
+            .filter(|page| {
+                matches!(
+                    page.header.type_,
+                    PageType::DICTIONARY_PAGE | PageType::DATA_PAGE | PageType::DATA_PAGE_V2
+                )
+            })
+            .collect();
+        let mismatch = |decoded: usize| {
+            ParquetError::General(format!(
+                "Column chunk {path} of row group {index} has {} page headers, but {decoded} pages were decoded",
+                headers.len()
+            ))
+        };
+
+        let row_group = SerializedRowGroupReader::new(
+            reader.clone(),
+            metadata.row_group(index),
+            None,
+            props.clone(),
+        )?;
+        let mut decoded = 0;
+        for page in row_group.get_column_page_reader(column)? {
+            let page = page?;
+            let raw = headers.get(decoded).ok_or_else(|| mismatch(decoded + 1))?;
+            decoded += 1;
+            let (num_rows, num_nulls, statistics) = match &page {
+                Page::DataPage { statistics, .. } => (None, None, statistics.as_ref()),
+                Page::DataPageV2 {
+                    num_rows,
+                    num_nulls,
+                    statistics,
+                    ..
+                } => (Some(*num_rows), Some(*num_nulls), statistics.as_ref()),
+                Page::DictionaryPage { .. } => (None, None, None),
+            };
+            details.push(PageDetails {
+                row_group: index,
+                offset: start + raw.offset as u64,
+                page_type: page.page_type().to_string(),
+                encoding: page.encoding().to_string(),
+                num_values: page.num_values(),
+                num_rows,
+                num_nulls,
+                header_size: raw.data.start - raw.offset,
+                compressed_size: raw.data.len(),
+                uncompressed_size: raw.header.uncompressed_page_size as usize,
+                crc: raw.header.crc.map(|crc| crc as u32),
+                statistics: statistics.map(chunk_statistics),
+            });
+        }
+        if decoded < headers.len() {
+            return Err(mismatch(decoded).into());
+        }
+    }
+    Ok(details)
+}
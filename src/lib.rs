@@ -1,18 +1,56 @@
-use arrow::array::RecordBatch;
-use arrow::ipc::reader::FileReader as ArrowFileReader;
-use arrow_schema::SchemaRef;
-use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
-use parquet::arrow::{ProjectionMask, parquet_to_arrow_schema};
-use parquet::file::reader::{FileReader, SerializedFileReader};
+use arrow::array::{RecordBatch, RecordBatchReader};
+use arrow::buffer::Buffer;
+use arrow::compute::{BatchCoalescer, filter_record_batch};
+use arrow::error::ArrowError;
+use arrow::ipc::convert::fb_to_schema;
+use arrow::ipc::reader::{FileDecoder, StreamReader as ArrowStreamReader, read_footer_length};
+use arrow::ipc::{Block, MetadataVersion, root_as_footer, root_as_message};
+use arrow_schema::{DataType, Fields, Schema, SchemaRef};
+use bytes::Bytes;
+use parquet::arrow::ProjectionMask;
+use parquet::arrow::arrow_reader::{
+    ArrowPredicateFn, ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReader,
+    ParquetRecordBatchReaderBuilder, RowFilter, RowSelection, RowSelector,
+};
+use parquet::file::metadata::{ParquetMetaData, RowGroupMetaData};
+use parquet::file::page_index::index::Index;
+use parquet::file::reader::{ChunkReader, Length};
+use parquet::file::statistics::Statistics;
 use sqlparser::dialect::PostgreSqlDialect;
 use sqlparser::parser::Parser;
 use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom, Write};
+use std::ops::RangeInclusive;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use thiserror::Error;
 
+mod audit;
 #[cfg(feature = "ffi")]
 pub mod ffi;
+mod filter;
+mod inspect;
+mod probe;
+mod profile;
+mod projection;
+mod recover;
+mod schema_tree;
+mod stats;
+mod validate;
+mod value_counts;
+
+pub use audit::{ChunkStatisticsAudit, StatisticsMismatch};
+pub use filter::{CompareOp, Filter, Literal};
+pub use inspect::{LeafColumn, LevelValue, PageDetails};
+pub use probe::{BloomFilterCheck, RowGroupProbe};
+pub use profile::{ColumnProfile, Distribution, profile_batches};
+use projection::Projection;
+pub use projection::select_columns;
+pub use recover::{LostRowGroup, RecoveryReport};
+pub use schema_tree::SchemaNode;
+pub use stats::{ColumnStatisticsSummary, StatisticsIssue};
+pub use validate::{ValidationIssue, ValidationReport};
+pub use value_counts::{ValueCount, ValueCounts};
 
 #[derive(Error, Debug)]
 pub enum ParquetViewerError {
@@ -26,6 +64,36 @@ pub enum ParquetViewerError {
     FileNotFound(String),
     #[error("SQL parser error: {0}")]
     SqlParser(#[from] sqlparser::parser::ParserError),
+    #[error("Invalid filter: {0}")]
+    InvalidFilter(String),
+    #[error("Unknown column '{name}'{}", did_you_mean(.suggestions))]
+    UnknownColumn {
+        name: String,
+        /// Existing columns with a similar name.
+        suggestions: Vec<String>,
+    },
+    #[error("Column index {index} is out of range for a schema with {num_columns} columns")]
+    ColumnIndexOutOfRange { index: usize, num_columns: usize },
+    #[error("Invalid column selector: {0}")]
+    InvalidColumnSelector(String),
+    #[error("Row group {index} is out of range for a file with {num_row_groups} row groups")]
+    RowGroupOutOfRange { index: usize, num_row_groups: usize },
+    #[error("{0} requires a Parquet file")]
+    ParquetRequired(String),
+    #[error("Invalid value: {0}")]
+    InvalidValue(String),
+    #[error("Unknown file format, magic bytes {0} are neither Parquet nor Arrow")]
+    UnknownFormat(String),
+    #[error("Cannot recover file: {0}")]
+    Unrecoverable(String),
+}
+
+fn did_you_mean(suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        return String::new();
+    }
+    let suggestions: Vec<_> = suggestions.iter().map(|s| format!("'{s}'")).collect();
+    format!(", did you mean {}?", suggestions.join(" or "))
 }
 
 pub type Result<T> = std::result::Result<T, ParquetViewerError>;
@@ -39,14 +107,110 @@ pub struct FileMetadata {
     pub version: i32,
     pub created_by: Option<String>,
     pub key_value_metadata: Option<Vec<(String, String)>>,
+    /// Whether the Parquet page index holds per page statistics.
+    pub has_column_index: bool,
+    /// Whether the Parquet page index holds page locations, used to skip
+    /// pages outside the rows being read.
+    pub has_offset_index: bool,
+    /// Layout of every row group, only present for Parquet files read with
+    /// [`ParquetViewerFile::detailed_metadata`].
+    pub row_groups: Option<Vec<RowGroupDetails>>,
+    /// Footer details, only present for Arrow IPC files.
+    pub ipc: Option<IpcMetadata>,
+}
+
+/// Physical layout of a Parquet row group, from the footer.
+#[derive(Debug, Clone)]
+pub struct RowGroupDetails {
+    pub num_rows: i64,
+    /// Uncompressed size of all column chunks.
+    pub total_byte_size: i64,
+    pub compressed_size: i64,
+    /// Offset of the first page of the row group, if recorded by the writer.
+    pub file_offset: Option<i64>,
+    /// Sort order of the rows, if declared by the writer.
+    pub sorting_columns: Option<Vec<SortingColumnDetails>>,
+    pub columns: Vec<ColumnChunkDetails>,
+}
+
+/// A column the rows of a row group are sorted by.
+#[derive(Debug, Clone)]
+pub struct SortingColumnDetails {
+    /// Dotted path of the leaf column, or the raw column index when the
+    /// footer refers to a column that does not exist.
+    pub column: String,
+    pub descending: bool,
+    pub nulls_first: bool,
+}
+
+/// Physical layout of one column chunk of a row group.
+#[derive(Debug, Clone)]
+pub struct ColumnChunkDetails {
+    /// Dotted path of the leaf column.
+    pub path: String,
+    pub physical_type: String,
+    pub codec: String,
+    pub encodings: Vec<String>,
+    pub num_values: i64,
+    pub compressed_size: i64,
+    pub uncompressed_size: i64,
+    pub data_page_offset: i64,
+    pub dictionary_page_offset: Option<i64>,
+    /// Location of the bloom filter of the column chunk, if it has one. The
+    /// length is only recorded by recent writers.
+    pub bloom_filter_offset: Option<i64>,
+    pub bloom_filter_length: Option<i32>,
+    pub statistics: Option<ChunkStatistics>,
+}
+
+/// Statistics stored in the footer for a column chunk.
+///
+/// Min and max are rendered from the physical values, byte arrays as UTF-8
+/// when valid and as hex otherwise.
+#[derive(Debug, Clone)]
+pub struct ChunkStatistics {
+    pub min: Option<String>,
+    pub max: Option<String>,
+    pub null_count: Option<u64>,
+    pub distinct_count: Option<u64>,
+    /// Whether min and max are the actual values, rather than truncated
+    /// bounds.
+    pub is_min_exact: bool,
+    pub is_max_exact: bool,
+}
+
+/// Details read from the footer of an Arrow IPC file.
+#[derive(Debug, Clone)]
+pub struct IpcMetadata {
+    /// IPC metadata version, e.g. `V5`.
+    pub metadata_version: String,
+    /// Body compression codec of the record batches, if any.
+    pub compression: Option<String>,
+    /// Record batch blocks in file order.
+    pub blocks: Vec<IpcBlock>,
+}
+
+/// Location of a single record batch inside an Arrow IPC file.
+#[derive(Debug, Clone)]
+pub struct IpcBlock {
+    pub offset: i64,
+    pub metadata_length: i32,
+    pub body_length: i64,
+    pub num_rows: i64,
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum FileFormat {
     Parquet,
+    /// Arrow IPC file format (Feather v2), with a footer.
     Arrow,
+    /// Arrow IPC streaming format, without a footer.
+    ArrowStream,
 }
 
+/// Continuation marker that starts every message in an Arrow IPC stream.
+const ARROW_STREAM_CONTINUATION: [u8; 4] = [0xFF; 4];
+
 fn detect_file_format(file_path: &Path) -> Result<FileFormat> {
     let extension = file_path
         .extension()
@@ -55,410 +219,3571 @@ fn detect_file_format(file_path: &Path) -> Result<FileFormat> {
 
     match extension.as_deref() {
         Some("parquet") => Ok(FileFormat::Parquet),
-        Some("arrow") | Some("arrows") | Some("ipc") | Some("feather") => Ok(FileFormat::Arrow),
+        Some("arrow") | Some("ipc") | Some("feather") => Ok(FileFormat::Arrow),
+        Some("arrows") => Ok(FileFormat::ArrowStream),
         _ => {
             // Try to detect by reading file magic bytes
             let file = File::open(file_path)?;
             let mut reader = std::io::BufReader::new(file);
             let mut magic = [0u8; 4];
-            use std::io::Read;
             reader.read_exact(&mut magic)?;
-
-            if &magic == b"PAR1" {
-                Ok(FileFormat::Parquet)
-            } else if &magic == b"ARRO" {
-                Ok(FileFormat::Arrow)
-            } else {
-                // Default to Parquet for backward compatibility
-                Ok(FileFormat::Parquet)
-            }
+            format_from_magic(&magic).ok_or_else(|| unknown_format(&magic))
         }
     }
 }
 
-pub fn read_schema(file_path: &Path) -> Result<SchemaRef> {
-    if !file_path.exists() {
-        return Err(ParquetViewerError::FileNotFound(
-            file_path.display().to_string(),
-        ));
+/// Detect the format of an in-memory or custom source from its magic bytes.
+fn detect_reader_format<R: ChunkReader>(reader: &R) -> Result<FileFormat> {
+    let magic = reader.get_bytes(0, reader.len().min(4) as usize)?;
+    format_from_magic(&magic).ok_or_else(|| unknown_format(&magic))
+}
+
+/// Detect the format of a payload from its leading magic bytes, `None` when
+/// they match no known format.
+pub fn format_from_magic(magic: &[u8]) -> Option<FileFormat> {
+    if magic.starts_with(b"PAR1") {
+        Some(FileFormat::Parquet)
+    } else if magic.starts_with(b"ARRO") {
+        Some(FileFormat::Arrow)
+    } else if magic.starts_with(&ARROW_STREAM_CONTINUATION) {
+        Some(FileFormat::ArrowStream)
+    } else {
+        None
     }
+}
 
-    let format = detect_file_format(file_path)?;
+fn unknown_format(magic: &[u8]) -> ParquetViewerError {
+    let magic: String = magic.iter().map(|b| format!("{b:02x}")).collect();
+    ParquetViewerError::UnknownFormat(magic)
+}
 
-    match format {
-        FileFormat::Parquet => {
-            let file = File::open(file_path)?;
-            let reader = SerializedFileReader::new(file)?;
-            let parquet_metadata = reader.metadata();
-            let file_metadata = parquet_metadata.file_metadata();
+/// Parsed footer of an opened file, kept so that it is only read once.
+enum Footer {
+    Parquet(ArrowReaderMetadata),
+    Arrow(ArrowFooter),
+    /// IPC streams have no footer, the schema is read from the first message.
+    ArrowStream,
+}
 
-            let arrow_schema = parquet_to_arrow_schema(
-                file_metadata.schema_descr(),
-                file_metadata.key_value_metadata(),
-            )?;
+/// Block locations from the footer of an Arrow IPC file.
+struct ArrowFooter {
+    version: MetadataVersion,
+    dictionaries: Vec<Block>,
+    record_batches: Vec<Block>,
+}
 
-            Ok(Arc::new(arrow_schema))
-        }
-        FileFormat::Arrow => {
-            let file = File::open(file_path)?;
-            let reader = ArrowFileReader::try_new(file, None)?;
-            Ok(reader.schema())
+/// A Parquet or Arrow file that has been opened and whose footer has been
+/// parsed once.
+///
+/// Schema, metadata and data can be read any number of times from the same
+/// handle without re-opening the file or parsing the footer again. Besides
+/// files on disk, any [`ChunkReader`] such as [`Bytes`] can be used as the
+/// source, and [`SeekReader`] adapts any `Read + Seek` source.
+pub struct ParquetViewerFile<R = File> {
+    reader: Arc<BoundedReader<R>>,
+    format: FileFormat,
+    schema: SchemaRef,
+    footer: Footer,
+}
+
+impl ParquetViewerFile<File> {
+    /// Open a file, detect its format and parse its footer.
+    pub fn open(file_path: &Path) -> Result<Self> {
+        if !file_path.exists() {
+            return Err(ParquetViewerError::FileNotFound(
+                file_path.display().to_string(),
+            ));
         }
+
+        let format = detect_file_format(file_path)?;
+        Self::with_format(File::open(file_path)?, format)
     }
 }
 
-pub fn read_metadata(file_path: &Path) -> Result<FileMetadata> {
-    if !file_path.exists() {
-        return Err(ParquetViewerError::FileNotFound(
-            file_path.display().to_string(),
-        ));
+impl<R: ChunkReader + 'static> ParquetViewerFile<R> {
+    /// Wrap an in-memory or custom source, detecting its format from the
+    /// magic bytes.
+    pub fn try_new(reader: R) -> Result<Self> {
+        let format = detect_reader_format(&reader)?;
+        Self::with_format(reader, format)
     }
 
-    let file_size = std::fs::metadata(file_path)?.len() as usize;
-    let format = detect_file_format(file_path)?;
+    fn with_format(reader: R, format: FileFormat) -> Result<Self> {
+        let reader = Arc::new(BoundedReader(reader));
 
-    match format {
-        FileFormat::Parquet => {
-            let file = File::open(file_path)?;
-            let reader = SerializedFileReader::new(file)?;
-            let parquet_metadata = reader.metadata();
-            let file_metadata = parquet_metadata.file_metadata();
+        let (schema, footer) = match format {
+            FileFormat::Parquet => {
+                let metadata = load_parquet_metadata(&*reader)?;
+                (metadata.schema().clone(), Footer::Parquet(metadata))
+            }
+            FileFormat::Arrow => {
+                let (schema, footer) = read_arrow_footer(&*reader)?;
+                (Arc::new(schema), Footer::Arrow(footer))
+            }
+            FileFormat::ArrowStream => {
+                let stream = ArrowStreamReader::try_new_buffered(ChunkCursor::new(&reader), None)?;
+                (stream.schema(), Footer::ArrowStream)
+            }
+        };
 
-            let total_records = parquet_metadata
-                .row_groups()
-                .iter()
-                .map(|rg| rg.num_rows())
-                .sum();
+        Ok(Self {
+            reader,
+            format,
+            schema,
+            footer,
+        })
+    }
 
-            let total_fields = file_metadata.schema().get_fields().len();
-            let total_row_groups = parquet_metadata.num_row_groups();
-            let version = file_metadata.version();
-            let created_by = file_metadata.created_by().map(|s| s.to_string());
+    pub fn format(&self) -> FileFormat {
+        self.format
+    }
 
-            let key_value_metadata = file_metadata.key_value_metadata().map(|kv_pairs| {
-                kv_pairs
-                    .iter()
-                    .map(|kv| (kv.key.clone(), kv.value.clone().unwrap_or_default()))
-                    .collect()
-            });
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
 
-            Ok(FileMetadata {
-                file_size,
-                total_records,
-                total_fields,
-                total_row_groups,
-                version,
-                created_by,
-                key_value_metadata,
-            })
-        }
-        FileFormat::Arrow => {
-            let file = File::open(file_path)?;
-            let reader = ArrowFileReader::try_new(file, None)?;
-            let schema = reader.schema();
-
-            // Count total records by iterating through batches
-            let mut total_records = 0i64;
-            let mut batch_count = 0;
-            for batch in reader {
-                let batch = batch?;
-                total_records += batch.num_rows() as i64;
-                batch_count += 1;
+    /// Hierarchy of the schema, with the Parquet repetition, physical and
+    /// logical types of every field for Parquet files.
+    pub fn schema_tree(&self) -> SchemaNode {
+        match &self.footer {
+            Footer::Parquet(metadata) => {
+                let descr = metadata.metadata().file_metadata().schema_descr();
+                SchemaNode::from_parquet(descr.root_schema(), &self.schema)
             }
-
-            Ok(FileMetadata {
-                file_size,
-                total_records,
-                total_fields: schema.fields().len(),
-                total_row_groups: batch_count, // Arrow doesn't have row groups, using batch count
-                version: 0,                    // Arrow IPC doesn't have version like Parquet
-                created_by: Some("Arrow IPC".to_string()),
-                key_value_metadata: if schema.metadata().is_empty() {
-                    None
-                } else {
-                    Some(
-                        schema
-                            .metadata()
-                            .iter()
-                            .map(|(k, v)| (k.clone(), v.clone()))
-                            .collect(),
-                    )
-                },
-            })
+            _ => SchemaNode::from_arrow(&self.schema),
         }
     }
-}
 
-pub fn read_data(
-    file_path: &Path,
-    batch_size: Option<usize>,
-    limit: Option<usize>,
-) -> Result<Vec<RecordBatch>> {
-    if !file_path.exists() {
-        return Err(ParquetViewerError::FileNotFound(
-            file_path.display().to_string(),
-        ));
-    }
+    pub fn metadata(&self) -> Result<FileMetadata> {
+        let file_size = self.reader.len() as usize;
 
-    let format = detect_file_format(file_path)?;
+        match &self.footer {
+            Footer::Parquet(metadata) => {
+                let parquet_metadata = metadata.metadata();
+                let file_metadata = parquet_metadata.file_metadata();
 
-    match format {
-        FileFormat::Parquet => {
-            let file = File::open(file_path)?;
-            let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
+                let total_records = parquet_metadata
+                    .row_groups()
+                    .iter()
+                    .map(|rg| rg.num_rows())
+                    .sum();
 
-            let reader = if let Some(batch_size) = batch_size {
-                builder.with_batch_size(batch_size).build()?
-            } else {
-                builder.build()?
-            };
+                let total_fields = file_metadata.schema().get_fields().len();
+                let total_row_groups = parquet_metadata.num_row_groups();
+                let version = file_metadata.version();
+                let created_by = file_metadata.created_by().map(|s| s.to_string());
+
+                let key_value_metadata = file_metadata.key_value_metadata().map(|kv_pairs| {
+                    kv_pairs
+                        .iter()
+                        .map(|kv| (kv.key.clone(), kv.value.clone().unwrap_or_default()))
+                        .collect()
+                });
+
+                // Columns written without statistics have an empty column index
+                let has_column_index = parquet_metadata.column_index().is_some_and(|index| {
+                    index
+                        .iter()
+                        .flatten()
+                        .any(|index| !matches!(index, Index::NONE))
+                });
+
+                Ok(FileMetadata {
+                    file_size,
+                    total_records,
+                    total_fields,
+                    total_row_groups,
+                    version,
+                    created_by,
+                    key_value_metadata,
+                    has_column_index,
+                    has_offset_index: parquet_metadata.offset_index().is_some(),
+                    row_groups: None,
+                    ipc: None,
+                })
+            }
+            Footer::Arrow(footer) => {
+                let ipc = read_ipc_metadata(&*self.reader, footer)?;
+                let total_records = ipc.blocks.iter().map(|block| block.num_rows).sum();
+                let batch_count = ipc.blocks.len();
+
+                Ok(arrow_ipc_metadata(
+                    file_size,
+                    &self.schema,
+                    "Arrow IPC",
+                    total_records,
+                    batch_count,
+                    Some(ipc),
+                ))
+            }
+            Footer::ArrowStream => {
+                let reader =
+                    ArrowStreamReader::try_new_buffered(ChunkCursor::new(&self.reader), None)?;
 
-            let mut batches = Vec::new();
-            let mut count = 0;
-            for batch in reader {
-                let batch = batch?;
-                count += batch.num_rows();
-                batches.push(batch);
-                if let Some(limit) = limit
-                    && count >= limit
-                {
-                    break;
+                // Streams have no footer, so count total records by iterating through batches
+                let mut total_records = 0i64;
+                let mut batch_count = 0;
+                for batch in reader {
+                    let batch = batch?;
+                    total_records += batch.num_rows() as i64;
+                    batch_count += 1;
                 }
+
+                Ok(arrow_ipc_metadata(
+                    file_size,
+                    &self.schema,
+                    "Arrow IPC stream",
+                    total_records,
+                    batch_count,
+                    None,
+                ))
             }
+        }
+    }
 
-            Ok(batches)
+    /// Like [`metadata`](Self::metadata), also describing the layout of
+    /// every row group of Parquet files.
+    pub fn detailed_metadata(&self) -> Result<FileMetadata> {
+        let mut metadata = self.metadata()?;
+        if let Footer::Parquet(parquet_metadata) = &self.footer {
+            let row_groups = parquet_metadata.metadata().row_groups();
+            metadata.row_groups = Some(row_groups.iter().map(row_group_details).collect());
         }
-        FileFormat::Arrow => {
-            let file = File::open(file_path)?;
-            let reader = ArrowFileReader::try_new(file, None)?;
+        Ok(metadata)
+    }
 
-            let mut batches = Vec::new();
-            for batch in reader {
-                batches.push(batch?);
+    /// Open a streaming reader configured by [`ReadOptions`].
+    pub fn read_batches(&self, options: &ReadOptions) -> Result<BatchReader> {
+        match &self.footer {
+            Footer::Parquet(metadata) => self.read_parquet_batches(metadata.clone(), options),
+            Footer::Arrow(footer) => {
+                let blocks =
+                    select_row_groups(options.row_groups.as_deref(), footer.record_batches.len())?;
+                let reader =
+                    ArrowBlockReader::try_new(self.reader.clone(), self.schema(), footer, &blocks)?;
+                BatchReader::from_arrow(Box::new(reader), self.schema(), options)
             }
-
-            Ok(batches)
+            Footer::ArrowStream => read_ipc_stream(ChunkCursor::new(&self.reader), options),
         }
     }
-}
 
-pub fn read_data_with_projection(
-    file_path: &Path,
-    column_indices: Vec<usize>,
-    batch_size: Option<usize>,
-    limit: Option<usize>,
-) -> Result<Vec<RecordBatch>> {
-    if !file_path.exists() {
-        return Err(ParquetViewerError::FileNotFound(
-            file_path.display().to_string(),
-        ));
+    /// Open a streaming reader over a Parquet file, decoding columns to the
+    /// Arrow types of `metadata`.
+    fn read_parquet_batches(
+        &self,
+        metadata: ArrowReaderMetadata,
+        options: &ReadOptions,
+    ) -> Result<BatchReader> {
+        let mut builder = ParquetRecordBatchReaderBuilder::new_with_metadata(
+            SharedReader(self.reader.clone()),
+            metadata,
+        );
+
+        if let Some(projection) = options.projection(builder.schema())? {
+            let mask = projection.parquet_mask(builder.schema(), builder.parquet_schema());
+            builder = builder.with_projection(mask);
+        }
+        if let Some(batch_size) = options.batch_size {
+            builder = builder.with_batch_size(batch_size);
+        }
+
+        let row_groups = select_row_groups(
+            options.row_groups.as_deref(),
+            builder.metadata().num_row_groups(),
+        )?;
+
+        // The offset counts matching rows, so with a filter it can only be
+        // applied after decoding
+        let skip = match &options.filter {
+            Some(filter) => {
+                builder = filter_parquet_rows(builder, row_groups, filter)?;
+                options.offset
+            }
+            None => {
+                builder = select_parquet_rows(builder, row_groups, options.offset, options.limit);
+                0
+            }
+        };
+
+        Ok(BatchReader::from_parquet(builder.build()?, skip, options))
     }
 
-    let format = detect_file_format(file_path)?;
+    /// Open a streaming reader over the given root columns.
+    pub fn read_batches_with_projection(
+        &self,
+        column_indices: Vec<usize>,
+        batch_size: Option<usize>,
+        limit: Option<usize>,
+    ) -> Result<BatchReader> {
+        let options = ReadOptions {
+            batch_size,
+            limit,
+            column_indices: Some(column_indices),
+            ..Default::default()
+        };
+        self.read_batches(&options)
+    }
 
-    match format {
-        FileFormat::Parquet => {
-            let file = File::open(file_path)?;
-            let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
+    /// Read all batches selected by [`ReadOptions`] into memory.
+    pub fn read_data(&self, options: &ReadOptions) -> Result<Vec<RecordBatch>> {
+        self.read_batches(options)?.collect()
+    }
 
-            let mask = ProjectionMask::roots(builder.parquet_schema(), column_indices.clone());
+    /// Scan the rows selected by [`ReadOptions`] once and profile every
+    /// column, see [`profile_batches`].
+    pub fn profile(&self, options: &ReadOptions) -> Result<Vec<ColumnProfile>> {
+        profile_batches(self.read_batches(options)?)
+    }
 
-            let reader = if let Some(batch_size) = batch_size {
-                builder
-                    .with_projection(mask)
-                    .with_batch_size(batch_size)
-                    .build()?
-            } else {
-                builder.with_projection(mask).build()?
-            };
+    /// Count the values of a column in the rows selected by [`ReadOptions`]
+    /// and return the `k` most frequent. The columns of `options` are
+    /// ignored, `column` can be a dotted path to a field of a struct column.
+    ///
+    /// Counts are exact unless the column has a very large number of distinct
+    /// values, see [`ValueCounts::max_error`].
+    pub fn top_values(&self, column: &str, k: usize, options: &ReadOptions) -> Result<ValueCounts> {
+        let depth = projection::field_path(&self.schema, column)?.len();
+        let options = ReadOptions {
+            columns: Some(vec![column.to_string()]),
+            column_indices: None,
+            exclude_columns: None,
+            ..options.clone()
+        };
 
-            let mut batches = Vec::new();
-            let mut count = 0;
-            for batch in reader {
-                let batch = batch?;
-                count += batch.num_rows();
-                batches.push(batch);
-                if let Some(limit) = limit
-                    && count >= limit
-                {
-                    break;
+        let reader = match &self.footer {
+            Footer::Parquet(metadata) => match dictionary_metadata(metadata, column)? {
+                Some(metadata) => {
+                    log::debug!("Counting dictionary keys of column '{column}'");
+                    self.read_parquet_batches(metadata, &options)?
                 }
-            }
+                None => self.read_parquet_batches(metadata.clone(), &options)?,
+            },
+            _ => self.read_batches(&options)?,
+        };
+        value_counts::count_values(reader, column, depth, k)
+    }
 
-            Ok(batches)
-        }
-        FileFormat::Arrow => {
-            let file = File::open(file_path)?;
-            let reader = ArrowFileReader::try_new(file, None)?;
-            let schema = reader.schema();
+    /// Aggregate the column chunk statistics of every row group into
+    /// file-level statistics per leaf column, without reading any pages.
+    pub fn column_statistics(&self) -> Result<Vec<ColumnStatisticsSummary>> {
+        let metadata = self.parquet_metadata("Column statistics")?;
+        Ok(stats::summarize_statistics(metadata))
+    }
 
-            // Create projected schema
-            let projected_fields: Vec<_> = column_indices
-                .iter()
-                .map(|&i| schema.field(i).clone())
-                .collect();
-            let projected_schema = Arc::new(arrow::datatypes::Schema::new(projected_fields));
-
-            let mut batches = Vec::new();
-            let mut count = 0;
-            for batch in reader {
-                let batch = batch?;
-                // Project columns
-                let projected_columns: Vec<_> = column_indices
-                    .iter()
-                    .map(|&i| batch.column(i).clone())
-                    .collect();
-                let projected_batch =
-                    RecordBatch::try_new(projected_schema.clone(), projected_columns)?;
-                count += projected_batch.num_rows();
-                batches.push(projected_batch);
-                if let Some(limit) = limit
-                    && count >= limit
-                {
-                    break;
-                }
-            }
+    /// Decode every column chunk and compare its min, max and null count
+    /// with the statistics stored in the footer, per row group and column.
+    pub fn audit_statistics(&self) -> Result<Vec<ChunkStatisticsAudit>> {
+        let metadata = self.parquet_metadata("Auditing statistics")?;
+        audit::audit_statistics(&self.reader, metadata)
+    }
 
-            Ok(batches)
-        }
+    /// Leaf columns of a Parquet file with their maximum repetition and
+    /// definition levels.
+    pub fn leaf_columns(&self) -> Result<Vec<LeafColumn>> {
+        let metadata = self.parquet_metadata("Inspecting levels")?;
+        Ok(inspect::leaf_columns(metadata))
     }
-}
 
-#[derive(Debug, Clone, Copy)]
-pub enum SqlFormatStyle {
-    Minimal,
-    Beautify,
-}
+    /// Read the first `limit` values of a Parquet leaf column, given its
+    /// dotted path, together with their repetition and definition levels.
+    pub fn read_levels(
+        &self,
+        column: &str,
+        row_group: Option<usize>,
+        limit: usize,
+    ) -> Result<Vec<LevelValue>> {
+        let metadata = self.parquet_metadata("Inspecting levels")?;
+        inspect::read_levels(&self.reader, metadata, column, row_group, limit)
+    }
 
-pub fn sql_format(sql: &str, style: SqlFormatStyle) -> Result<String> {
-    let dialect = PostgreSqlDialect {};
-    let ast = Parser::parse_sql(&dialect, sql)?;
+    /// Headers, sizes and statistics of the pages of a Parquet leaf column,
+    /// given its dotted path, in every row group or in one.
+    pub fn read_pages(&self, column: &str, row_group: Option<usize>) -> Result<Vec<PageDetails>> {
+        let metadata = self.parquet_metadata("Inspecting pages")?;
+        inspect::read_pages(&self.reader, metadata, column, row_group)
+    }
 
-    if ast.is_empty() {
-        return Ok(sql.to_string());
+    /// Check the bloom filter of a Parquet leaf column in every row group,
+    /// given its dotted path, for the rows groups that may contain `value`.
+    ///
+    /// The value is parsed according to the physical type of the column,
+    /// fixed length byte arrays such as UUIDs from hex.
+    pub fn probe(&self, column: &str, value: &str) -> Result<Vec<RowGroupProbe>> {
+        let metadata = self.parquet_metadata("Probing bloom filters")?;
+        probe::probe_bloom_filters(&self.reader, metadata, column, value)
     }
 
-    let sql = match style {
-        SqlFormatStyle::Minimal => {
-            // For minimal format
-            ast.iter().map(|stmt| stmt.to_string()).collect::<Vec<_>>()
-        }
-        SqlFormatStyle::Beautify => {
-            // For beautify format, see https://docs.rs/sqlparser/latest/sqlparser/#pretty-printing
-            ast.iter()
-                .map(|stmt| format!("{:#}", stmt))
-                .collect::<Vec<_>>()
+    /// Footer of a Parquet file, or an error naming the operation that needs
+    /// one.
+    fn parquet_metadata(&self, operation: &str) -> Result<&ParquetMetaData> {
+        match &self.footer {
+            Footer::Parquet(metadata) => Ok(metadata.metadata()),
+            _ => Err(ParquetViewerError::ParquetRequired(operation.to_string())),
         }
-    };
-    Ok(sql.join(";\n\n"))
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use arrow::array::{Int32Array, StringArray};
-    use arrow::datatypes::{DataType, Field, Schema};
-    use arrow::ipc::writer::FileWriter as ArrowFileWriter;
-    use arrow::record_batch::RecordBatch;
-    use parquet::arrow::ArrowWriter;
-    use std::sync::Arc;
-    use tempfile::NamedTempFile;
+/// Reader metadata decoding a string or binary column to an Arrow dictionary,
+/// if every column chunk of it is dictionary encoded. `column` can be a
+/// dotted path to a field of a struct column.
+///
+/// The values of each dictionary page are then decoded once, and data pages
+/// only produce keys. Data pages are still read, since they hold the number
+/// of times each key occurs. Leaves inside lists or maps are read as plain
+/// values.
+fn dictionary_metadata(
+    metadata: &ArrowReaderMetadata,
+    column: &str,
+) -> Result<Option<ArrowReaderMetadata>> {
+    let schema = metadata.schema();
+    let path = projection::field_path(schema, column)?;
+    let mut fields = schema.fields();
+    let mut names = Vec::with_capacity(path.len());
+    for &index in &path[..path.len() - 1] {
+        let DataType::Struct(children) = fields[index].data_type() else {
+            unreachable!("field paths only lead through struct columns");
+        };
+        names.push(fields[index].name().as_str());
+        fields = children;
+    }
+    let field = &fields[path[path.len() - 1]];
+    names.push(field.name());
+    if !matches!(
+        field.data_type(),
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Binary | DataType::LargeBinary
+    ) {
+        return Ok(None);
+    }
 
-    fn create_test_parquet_file() -> NamedTempFile {
-        let temp_file = NamedTempFile::new().unwrap();
+    let parquet_metadata = metadata.metadata();
+    let descr = parquet_metadata.file_metadata().schema_descr();
+    let Some(leaf) = (0..descr.num_columns()).find(|&i| descr.column(i).path().parts() == names)
+    else {
+        return Ok(None);
+    };
+    let dictionary_encoded = parquet_metadata
+        .row_groups()
+        .iter()
+        .all(|row_group| row_group.column(leaf).dictionary_page_offset().is_some());
+    if !dictionary_encoded {
+        return Ok(None);
+    }
 
-        let schema = Arc::new(Schema::new(vec![
-            Field::new("id", DataType::Int32, false),
-            Field::new("name", DataType::Utf8, false),
-        ]));
+    let fields = with_dictionary_leaf(schema.fields(), &path);
+    let schema = Schema::new_with_metadata(fields, schema.metadata().clone());
+    let options = ArrowReaderOptions::new().with_schema(Arc::new(schema));
+    Ok(Some(ArrowReaderMetadata::try_new(
+        parquet_metadata.clone(),
+        options,
+    )?))
+}
 
-        let id_array = Int32Array::from(vec![1, 2, 3, 4, 5]);
-        let name_array = StringArray::from(vec!["Alice", "Bob", "Charlie", "David", "Eve"]);
+/// Fields with the leaf at the end of `path` turned into an Arrow dictionary
+/// of its type, through the struct columns leading to it.
+fn with_dictionary_leaf(fields: &Fields, path: &[usize]) -> Fields {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            if index != path[0] {
+                return field.clone();
+            }
+            let data_type = match (field.data_type(), path.len()) {
+                (DataType::Struct(children), 2..) => {
+                    DataType::Struct(with_dictionary_leaf(children, &path[1..]))
+                }
+                (data_type, _) => {
+                    DataType::Dictionary(Box::new(DataType::Int32), Box::new(data_type.clone()))
+                }
+            };
+            Arc::new(field.as_ref().clone().with_data_type(data_type))
+        })
+        .collect()
+}
 
-        let batch = RecordBatch::try_new(
-            schema.clone(),
-            vec![Arc::new(id_array), Arc::new(name_array)],
-        )
-        .unwrap();
+/// Load the Parquet footer together with the page index, if the file has one.
+///
+/// The offset index lets row selections skip whole pages, and the column
+/// index lets filters skip pages by their statistics.
+fn load_parquet_metadata<R: ChunkReader>(reader: &R) -> Result<ArrowReaderMetadata> {
+    let options = ArrowReaderOptions::new().with_page_index(true);
+    match ArrowReaderMetadata::load(reader, options) {
+        Ok(metadata) => Ok(metadata),
+        Err(e) => {
+            // A page index written for only some of the columns cannot be
+            // loaded, but the rest of the footer is still usable
+            log::debug!("Reading Parquet footer without page index: {e}");
+            Ok(ArrowReaderMetadata::load(
+                reader,
+                ArrowReaderOptions::new(),
+            )?)
+        }
+    }
+}
 
-        let file = temp_file.reopen().unwrap();
-        let mut writer = ArrowWriter::try_new(file, schema, None).unwrap();
-        writer.write(&batch).unwrap();
-        writer.close().unwrap();
+/// Shares one underlying reader between the footer cache and any number of
+/// Parquet record batch readers.
+struct SharedReader<R>(Arc<R>);
 
-        temp_file
+impl<R: ChunkReader> Length for SharedReader<R> {
+    fn len(&self) -> u64 {
+        self.0.len()
     }
+}
 
-    #[test]
-    fn test_read_schema() {
-        let temp_file = create_test_parquet_file();
-        let schema = read_schema(temp_file.path()).unwrap();
+impl<R: ChunkReader> ChunkReader for SharedReader<R> {
+    type T = R::T;
 
-        assert_eq!(schema.fields().len(), 2);
-        assert_eq!(schema.field(0).name(), "id");
-        assert_eq!(schema.field(1).name(), "name");
-        assert_eq!(schema.field(0).data_type(), &DataType::Int32);
-        assert_eq!(schema.field(1).data_type(), &DataType::Utf8);
+    fn get_read(&self, start: u64) -> parquet::errors::Result<Self::T> {
+        self.0.get_read(start)
     }
 
-    #[test]
-    fn test_read_metadata() {
-        let temp_file = create_test_parquet_file();
-        let metadata = read_metadata(temp_file.path()).unwrap();
-
-        assert_eq!(metadata.total_records, 5);
-        assert_eq!(metadata.total_fields, 2);
-        assert_eq!(metadata.total_row_groups, 1);
-        assert!(metadata.file_size > 0);
+    fn get_bytes(&self, start: u64, length: usize) -> parquet::errors::Result<Bytes> {
+        self.0.get_bytes(start, length)
     }
+}
 
-    #[test]
-    fn test_read_data() {
-        let temp_file = create_test_parquet_file();
-        let batches = read_data(temp_file.path(), None, None).unwrap();
+/// Checks every read against the length of the source, so that a footer
+/// pointing past the end of a truncated file fails with an error rather than
+/// a panic in sources such as [`Bytes`], which panic on slices out of range.
+pub(crate) struct BoundedReader<R>(pub(crate) R);
 
-        assert_eq!(batches.len(), 1);
-        let batch = &batches[0];
-        assert_eq!(batch.num_rows(), 5);
-        assert_eq!(batch.num_columns(), 2);
+impl<R: ChunkReader> Length for BoundedReader<R> {
+    fn len(&self) -> u64 {
+        self.0.len()
     }
+}
 
-    #[test]
-    fn test_read_data_with_projection() {
-        let temp_file = create_test_parquet_file();
-        let batches = read_data_with_projection(temp_file.path(), vec![1], None, None).unwrap();
+impl<R: ChunkReader> ChunkReader for BoundedReader<R> {
+    type T = R::T;
 
-        assert_eq!(batches.len(), 1);
-        let batch = &batches[0];
-        assert_eq!(batch.num_rows(), 5);
-        assert_eq!(batch.num_columns(), 1);
-        assert_eq!(batch.schema().field(0).name(), "name");
+    fn get_read(&self, start: u64) -> parquet::errors::Result<Self::T> {
+        if start > self.len() {
+            return Err(parquet::errors::ParquetError::EOF(format!(
+                "Expected to read at offset {start}, past the end of {} bytes",
+                self.len()
+            )));
+        }
+        self.0.get_read(start)
     }
 
-    #[test]
-    fn test_file_not_found() {
-        let result = read_schema(Path::new("/nonexistent/file.parquet"));
-        assert!(matches!(result, Err(ParquetViewerError::FileNotFound(_))));
+    fn get_bytes(&self, start: u64, length: usize) -> parquet::errors::Result<Bytes> {
+        if start
+            .checked_add(length as u64)
+            .is_none_or(|end| end > self.len())
+        {
+            return Err(parquet::errors::ParquetError::EOF(format!(
+                "Expected to read {length} bytes at offset {start}, past the end of {} bytes",
+                self.len()
+            )));
+        }
+        self.0.get_bytes(start, length)
     }
+}
 
-    fn create_test_arrow_file() -> NamedTempFile {
-        let temp_file = NamedTempFile::new().unwrap();
+/// Adapts any `Read + Seek` source, such as a `Cursor` or a database blob
+/// handle, into a [`ChunkReader`].
+///
+/// Reads are serialized through a mutex, and every reader handed out keeps
+/// its own position so they can be interleaved.
+pub struct SeekReader<R> {
+    inner: Arc<Mutex<R>>,
+    len: u64,
+}
 
-        let schema = Arc::new(Schema::new(vec![
-            Field::new("id", DataType::Int32, false),
-            Field::new("name", DataType::Utf8, false),
-        ]));
+impl<R: Read + Seek> SeekReader<R> {
+    pub fn new(mut inner: R) -> Result<Self> {
+        let len = inner.seek(SeekFrom::End(0))?;
+        Ok(Self {
+            inner: Arc::new(Mutex::new(inner)),
+            len,
+        })
+    }
 
-        let id_array = Int32Array::from(vec![1, 2, 3, 4, 5]);
-        let name_array = StringArray::from(vec!["Alice", "Bob", "Charlie", "David", "Eve"]);
+    fn read_at(&self, position: u64, buf: &mut [u8]) -> std::io::Result<usize> {
+        let mut inner = self
+            .inner
+            .lock()
+            .map_err(|_| std::io::Error::other("source reader lock poisoned"))?;
+        inner.seek(SeekFrom::Start(position))?;
+        inner.read(buf)
+    }
+}
 
-        let batch = RecordBatch::try_new(
-            schema.clone(),
-            vec![Arc::new(id_array), Arc::new(name_array)],
-        )
-        .unwrap();
+impl<R: Read + Seek + Send> Length for SeekReader<R> {
+    fn len(&self) -> u64 {
+        self.len
+    }
+}
 
-        {
+impl<R: Read + Seek + Send> ChunkReader for SeekReader<R> {
+    type T = BufReader<SeekReaderCursor<R>>;
+
+    fn get_read(&self, start: u64) -> parquet::errors::Result<Self::T> {
+        Ok(BufReader::new(SeekReaderCursor {
+            reader: SeekReader {
+                inner: self.inner.clone(),
+                len: self.len,
+            },
+            position: start,
+        }))
+    }
+
+    fn get_bytes(&self, start: u64, length: usize) -> parquet::errors::Result<Bytes> {
+        let mut buf = vec![0u8; length];
+        let mut filled = 0;
+        while filled < length {
+            let read = self.read_at(start + filled as u64, &mut buf[filled..])?;
+            if read == 0 {
+                return Err(parquet::errors::ParquetError::EOF(format!(
+                    "Expected to read {length} bytes at offset {start}, found {filled}"
+                )));
+            }
+            filled += read;
+        }
+        Ok(buf.into())
+    }
+}
+
+/// Reader returned by [`SeekReader::get_read`].
+pub struct SeekReaderCursor<R> {
+    reader: SeekReader<R>,
+    position: u64,
+}
+
+impl<R: Read + Seek> Read for SeekReaderCursor<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.reader.read_at(self.position, buf)?;
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+/// `Read` adapter over a shared [`ChunkReader`] that tracks its own position,
+/// so that concurrent readers do not disturb each other.
+struct ChunkCursor<R> {
+    reader: Arc<R>,
+    position: u64,
+}
+
+impl<R> ChunkCursor<R> {
+    fn new(reader: &Arc<R>) -> Self {
+        Self {
+            reader: reader.clone(),
+            position: 0,
+        }
+    }
+}
+
+impl<R: ChunkReader> Read for ChunkCursor<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let available = self.reader.len().saturating_sub(self.position);
+        let length = available.min(buf.len() as u64) as usize;
+        if length == 0 {
+            return Ok(0);
+        }
+
+        let bytes = self
+            .reader
+            .get_bytes(self.position, length)
+            .map_err(std::io::Error::other)?;
+        buf[..length].copy_from_slice(&bytes);
+        self.position += length as u64;
+        Ok(length)
+    }
+}
+
+/// Parse the schema and block locations from the footer of an Arrow IPC file.
+fn read_arrow_footer<R: ChunkReader>(reader: &R) -> Result<(Schema, ArrowFooter)> {
+    // Space for the footer length (4 bytes) and ARROW_MAGIC (6 bytes)
+    let file_size = reader.len();
+    if file_size < 10 {
+        return Err(ArrowError::ParseError("Arrow file is too small".to_string()).into());
+    }
+    let trailer = reader.get_bytes(file_size - 10, 10)?;
+    let footer_len = read_footer_length(trailer[..].try_into().unwrap())?;
+    let footer_start = (file_size - 10)
+        .checked_sub(footer_len as u64)
+        .ok_or_else(|| ArrowError::ParseError(format!("Invalid footer length: {footer_len}")))?;
+
+    let footer_data = reader.get_bytes(footer_start, footer_len)?;
+    let footer = root_as_footer(&footer_data)
+        .map_err(|e| ArrowError::ParseError(format!("Unable to get root as footer: {e:?}")))?;
+
+    let ipc_schema = footer.schema().ok_or_else(|| {
+        ArrowError::ParseError("Unable to get schema from IPC footer".to_string())
+    })?;
+    if !ipc_schema.endianness().equals_to_target_endianness() {
+        return Err(ArrowError::IpcError(
+            "the endianness of the source system does not match the endianness of the target system."
+                .to_string(),
+        )
+        .into());
+    }
+
+    let footer = ArrowFooter {
+        version: footer.version(),
+        dictionaries: footer
+            .dictionaries()
+            .into_iter()
+            .flatten()
+            .copied()
+            .collect(),
+        record_batches: footer
+            .recordBatches()
+            .into_iter()
+            .flatten()
+            .copied()
+            .collect(),
+    };
+
+    Ok((fb_to_schema(ipc_schema), footer))
+}
+
+/// Read the row count and compression of every record batch from its message
+/// header, without decoding any record batch bodies.
+fn read_ipc_metadata<R: ChunkReader>(reader: &R, footer: &ArrowFooter) -> Result<IpcMetadata> {
+    let mut blocks = Vec::new();
+    let mut compression = None;
+    for block in &footer.record_batches {
+        let (offset, length) = ipc_block_range(reader.len(), block, false)?;
+        let message_data = reader.get_bytes(offset, length)?;
+
+        // Messages are prefixed by an optional continuation marker and the metadata length
+        let prefix_len = if message_data.starts_with(&ARROW_STREAM_CONTINUATION) {
+            8
+        } else {
+            4
+        };
+        let message = message_data
+            .get(prefix_len..)
+            .and_then(|data| root_as_message(data).ok())
+            .ok_or_else(|| {
+                ArrowError::ParseError(format!(
+                    "Unable to read record batch message at offset {}",
+                    block.offset()
+                ))
+            })?;
+        let record_batch = message.header_as_record_batch().ok_or_else(|| {
+            ArrowError::ParseError(format!(
+                "Expected record batch message at offset {}, found {:?}",
+                block.offset(),
+                message.header_type()
+            ))
+        })?;
+
+        if compression.is_none() {
+            compression = record_batch
+                .compression()
+                .map(|c| format!("{:?}", c.codec()));
+        }
+        blocks.push(IpcBlock {
+            offset: block.offset(),
+            metadata_length: block.metaDataLength(),
+            body_length: block.bodyLength(),
+            num_rows: record_batch.length(),
+        });
+    }
+
+    Ok(IpcMetadata {
+        metadata_version: format!("{:?}", footer.version),
+        compression,
+        blocks,
+    })
+}
+
+fn row_group_details(row_group: &RowGroupMetaData) -> RowGroupDetails {
+    let sorting_columns = row_group.sorting_columns().map(|sorting_columns| {
+        sorting_columns
+            .iter()
+            .map(|sorting_column| SortingColumnDetails {
+                column: usize::try_from(sorting_column.column_idx)
+                    .ok()
+                    .and_then(|idx| row_group.schema_descr().columns().get(idx))
+                    .map_or_else(
+                        || sorting_column.column_idx.to_string(),
+                        |column| column.path().string(),
+                    ),
+                descending: sorting_column.descending,
+                nulls_first: sorting_column.nulls_first,
+            })
+            .collect()
+    });
+
+    let columns = row_group
+        .columns()
+        .iter()
+        .map(|column| ColumnChunkDetails {
+            path: column.column_path().string(),
+            physical_type: column.column_type().to_string(),
+            codec: column.compression().to_string(),
+            encodings: column.encodings().iter().map(|e| e.to_string()).collect(),
+            num_values: column.num_values(),
+            compressed_size: column.compressed_size(),
+            uncompressed_size: column.uncompressed_size(),
+            data_page_offset: column.data_page_offset(),
+            dictionary_page_offset: column.dictionary_page_offset(),
+            bloom_filter_offset: column.bloom_filter_offset(),
+            bloom_filter_length: column.bloom_filter_length(),
+            statistics: column.statistics().map(chunk_statistics),
+        })
+        .collect();
+
+    RowGroupDetails {
+        num_rows: row_group.num_rows(),
+        total_byte_size: row_group.total_byte_size(),
+        compressed_size: row_group.compressed_size(),
+        file_offset: row_group.file_offset(),
+        sorting_columns,
+        columns,
+    }
+}
+
+fn chunk_statistics(statistics: &Statistics) -> ChunkStatistics {
+    fn bounds<T>(
+        min: Option<&T>,
+        max: Option<&T>,
+        format: impl Fn(&T) -> String,
+    ) -> (Option<String>, Option<String>) {
+        (min.map(&format), max.map(&format))
+    }
+
+    let (min, max) = match statistics {
+        Statistics::Boolean(s) => bounds(s.min_opt(), s.max_opt(), ToString::to_string),
+        Statistics::Int32(s) => bounds(s.min_opt(), s.max_opt(), ToString::to_string),
+        Statistics::Int64(s) => bounds(s.min_opt(), s.max_opt(), ToString::to_string),
+        Statistics::Int96(s) => bounds(s.min_opt(), s.max_opt(), ToString::to_string),
+        Statistics::Float(s) => bounds(s.min_opt(), s.max_opt(), ToString::to_string),
+        Statistics::Double(s) => bounds(s.min_opt(), s.max_opt(), ToString::to_string),
+        Statistics::ByteArray(s) => bounds(s.min_opt(), s.max_opt(), |v| format_bytes(v.data())),
+        Statistics::FixedLenByteArray(s) => {
+            bounds(s.min_opt(), s.max_opt(), |v| format_bytes(v.data()))
+        }
+    };
+
+    ChunkStatistics {
+        min,
+        max,
+        null_count: statistics.null_count_opt(),
+        distinct_count: statistics.distinct_count_opt(),
+        is_min_exact: statistics.min_is_exact(),
+        is_max_exact: statistics.max_is_exact(),
+    }
+}
+
+/// Render a binary statistics value as UTF-8 when valid, as hex otherwise.
+fn format_bytes(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => s.to_string(),
+        Err(_) => bytes.iter().map(|b| format!("{b:02x}")).collect(),
+    }
+}
+
+fn arrow_ipc_metadata(
+    file_size: usize,
+    schema: &Schema,
+    created_by: &str,
+    total_records: i64,
+    batch_count: usize,
+    ipc: Option<IpcMetadata>,
+) -> FileMetadata {
+    FileMetadata {
+        file_size,
+        total_records,
+        total_fields: schema.fields().len(),
+        total_row_groups: batch_count, // Arrow doesn't have row groups, using batch count
+        version: 0,                    // Arrow IPC doesn't have version like Parquet
+        created_by: Some(created_by.to_string()),
+        key_value_metadata: if schema.metadata().is_empty() {
+            None
+        } else {
+            Some(
+                schema
+                    .metadata()
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+            )
+        },
+        has_column_index: false,
+        has_offset_index: false,
+        row_groups: None,
+        ipc,
+    }
+}
+
+pub fn read_schema(file_path: &Path) -> Result<SchemaRef> {
+    Ok(ParquetViewerFile::open(file_path)?.schema())
+}
+
+/// Read the schema from an in-memory buffer such as [`Bytes`], or any other
+/// [`ChunkReader`].
+pub fn read_schema_from_reader<R: ChunkReader + 'static>(reader: R) -> Result<SchemaRef> {
+    Ok(ParquetViewerFile::try_new(reader)?.schema())
+}
+
+pub fn read_schema_tree(file_path: &Path) -> Result<SchemaNode> {
+    Ok(ParquetViewerFile::open(file_path)?.schema_tree())
+}
+
+/// Read the schema tree from an in-memory buffer such as [`Bytes`], or any
+/// other [`ChunkReader`].
+pub fn read_schema_tree_from_reader<R: ChunkReader + 'static>(reader: R) -> Result<SchemaNode> {
+    Ok(ParquetViewerFile::try_new(reader)?.schema_tree())
+}
+
+pub fn read_metadata(file_path: &Path) -> Result<FileMetadata> {
+    ParquetViewerFile::open(file_path)?.metadata()
+}
+
+/// Read metadata from an in-memory buffer such as [`Bytes`], or any other
+/// [`ChunkReader`].
+pub fn read_metadata_from_reader<R: ChunkReader + 'static>(reader: R) -> Result<FileMetadata> {
+    ParquetViewerFile::try_new(reader)?.metadata()
+}
+
+/// Read metadata including the layout of every row group, see
+/// [`ParquetViewerFile::detailed_metadata`].
+pub fn read_detailed_metadata(file_path: &Path) -> Result<FileMetadata> {
+    ParquetViewerFile::open(file_path)?.detailed_metadata()
+}
+
+pub fn read_detailed_metadata_from_reader<R: ChunkReader + 'static>(
+    reader: R,
+) -> Result<FileMetadata> {
+    ParquetViewerFile::try_new(reader)?.detailed_metadata()
+}
+
+pub fn profile_data(file_path: &Path, options: &ReadOptions) -> Result<Vec<ColumnProfile>> {
+    ParquetViewerFile::open(file_path)?.profile(options)
+}
+
+/// Profile an in-memory buffer such as [`Bytes`], or any other
+/// [`ChunkReader`].
+pub fn profile_data_from_reader<R: ChunkReader + 'static>(
+    reader: R,
+    options: &ReadOptions,
+) -> Result<Vec<ColumnProfile>> {
+    ParquetViewerFile::try_new(reader)?.profile(options)
+}
+
+pub fn top_values(
+    file_path: &Path,
+    column: &str,
+    k: usize,
+    options: &ReadOptions,
+) -> Result<ValueCounts> {
+    ParquetViewerFile::open(file_path)?.top_values(column, k, options)
+}
+
+/// Count the values of a column in an in-memory buffer such as [`Bytes`], or
+/// any other [`ChunkReader`].
+pub fn top_values_from_reader<R: ChunkReader + 'static>(
+    reader: R,
+    column: &str,
+    k: usize,
+    options: &ReadOptions,
+) -> Result<ValueCounts> {
+    ParquetViewerFile::try_new(reader)?.top_values(column, k, options)
+}
+
+pub fn read_column_statistics(file_path: &Path) -> Result<Vec<ColumnStatisticsSummary>> {
+    ParquetViewerFile::open(file_path)?.column_statistics()
+}
+
+/// Read column statistics from an in-memory buffer such as [`Bytes`], or any
+/// other [`ChunkReader`].
+pub fn read_column_statistics_from_reader<R: ChunkReader + 'static>(
+    reader: R,
+) -> Result<Vec<ColumnStatisticsSummary>> {
+    ParquetViewerFile::try_new(reader)?.column_statistics()
+}
+
+/// Recompute the statistics of every column chunk of a Parquet file and
+/// compare them with those stored in its footer.
+pub fn audit_statistics(file_path: &Path) -> Result<Vec<ChunkStatisticsAudit>> {
+    ParquetViewerFile::open(file_path)?.audit_statistics()
+}
+
+/// Audit statistics from an in-memory buffer such as [`Bytes`], or any other
+/// [`ChunkReader`].
+pub fn audit_statistics_from_reader<R: ChunkReader + 'static>(
+    reader: R,
+) -> Result<Vec<ChunkStatisticsAudit>> {
+    ParquetViewerFile::try_new(reader)?.audit_statistics()
+}
+
+/// Check that a Parquet file is intact: its magic bytes and footer, that
+/// every page decodes and matches its checksum, and that every column chunk
+/// holds as many rows and values as recorded.
+///
+/// Unlike other functions, this does not fail on a corrupt file but reports
+/// every problem found.
+pub fn validate_file(file_path: &Path) -> Result<ValidationReport> {
+    if !file_path.exists() {
+        return Err(ParquetViewerError::FileNotFound(
+            file_path.display().to_string(),
+        ));
+    }
+    validate::validate(File::open(file_path)?)
+}
+
+/// Validate a Parquet file from an in-memory buffer such as [`Bytes`], or any
+/// other [`ChunkReader`].
+pub fn validate_from_reader<R: ChunkReader + 'static>(reader: R) -> Result<ValidationReport> {
+    validate::validate(reader)
+}
+
+/// Write the intact row groups of a damaged Parquet file to a new file.
+///
+/// Row groups are located from the footer when it can be read. Files whose
+/// footer is missing, such as those of a writer that crashed, are scanned
+/// page by page using the schema of `schema_hint`, a Parquet or Arrow file
+/// written the same way. The schema embedded in a footer as `ARROW:schema`
+/// is lost with it, even when the footer was partially written, so files
+/// without a readable footer always need `schema_hint`.
+pub fn recover_file(
+    file_path: &Path,
+    output_path: &Path,
+    schema_hint: Option<&Path>,
+) -> Result<RecoveryReport> {
+    if !file_path.exists() {
+        return Err(ParquetViewerError::FileNotFound(
+            file_path.display().to_string(),
+        ));
+    }
+    check_recovery_output(output_path, file_path, "input")?;
+    recover_to_file(File::open(file_path)?, output_path, schema_hint)
+}
+
+/// Recover a damaged Parquet file from any [`ChunkReader`] to
+/// `output_path`.
+///
+/// The output is written to a temporary file next to it, and only replaces
+/// `output_path` once the recovery succeeded.
+pub fn recover_to_file<R: ChunkReader + 'static>(
+    reader: R,
+    output_path: &Path,
+    schema_hint: Option<&Path>,
+) -> Result<RecoveryReport> {
+    if let Some(schema_hint) = schema_hint {
+        check_recovery_output(output_path, schema_hint, "schema hint")?;
+    }
+    let file_name = output_path.file_name().ok_or_else(|| {
+        ParquetViewerError::InvalidValue(format!(
+            "output {} is not a file name",
+            output_path.display()
+        ))
+    })?;
+    // In the same directory, so that renaming it cannot cross file systems
+    let temp_path = output_path.with_file_name(format!(
+        ".{}.recover-{}",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+    let temp_file = File::options()
+        .write(true)
+        .create_new(true)
+        .open(&temp_path)?;
+
+    let result = recover::recover(reader, &temp_file, schema_hint).and_then(|report| {
+        temp_file.sync_all()?;
+        std::fs::rename(&temp_path, output_path)?;
+        Ok(report)
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// Refuse to recover to the input file or the schema hint, which would be
+/// truncated or replaced by the output.
+fn check_recovery_output(output_path: &Path, path: &Path, name: &str) -> Result<()> {
+    // An output that does not exist yet cannot be an existing file
+    if let (Ok(output), Ok(path)) = (output_path.canonicalize(), path.canonicalize())
+        && output == path
+    {
+        return Err(ParquetViewerError::Unrecoverable(format!(
+            "the output {} is the {name} file",
+            output_path.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Recover a damaged Parquet file from an in-memory buffer such as
+/// [`Bytes`], or any other [`ChunkReader`], writing to `output`.
+pub fn recover_from_reader<R: ChunkReader + 'static, W: Write + Send>(
+    reader: R,
+    output: W,
+    schema_hint: Option<&Path>,
+) -> Result<RecoveryReport> {
+    recover::recover(reader, output, schema_hint)
+}
+
+pub fn probe_bloom_filters(
+    file_path: &Path,
+    column: &str,
+    value: &str,
+) -> Result<Vec<RowGroupProbe>> {
+    ParquetViewerFile::open(file_path)?.probe(column, value)
+}
+
+/// Probe bloom filters from an in-memory buffer such as [`Bytes`], or any
+/// other [`ChunkReader`].
+pub fn probe_bloom_filters_from_reader<R: ChunkReader + 'static>(
+    reader: R,
+    column: &str,
+    value: &str,
+) -> Result<Vec<RowGroupProbe>> {
+    ParquetViewerFile::try_new(reader)?.probe(column, value)
+}
+
+/// Options controlling which rows and columns a [`BatchReader`] produces.
+#[derive(Debug, Clone, Default)]
+pub struct ReadOptions {
+    /// Number of rows per batch, `None` uses the reader default.
+    pub batch_size: Option<usize>,
+    /// Number of leading rows to skip.
+    pub offset: usize,
+    /// Maximum number of rows to return after the offset.
+    pub limit: Option<usize>,
+    /// Root column indices to read, `None` reads every column.
+    pub column_indices: Option<Vec<usize>>,
+    /// Names of the columns to read, in addition to `column_indices`.
+    /// Fields of struct columns are selected by their dotted path, e.g.
+    /// `payload.user.id`, and keep their enclosing struct. `re:<pattern>`
+    /// selects every column whose name matches, see [`select_columns`].
+    pub columns: Option<Vec<String>>,
+    /// Columns to leave out of `columns`, or out of all columns when
+    /// `columns` is `None`. Accepts the same selectors as `columns`.
+    pub exclude_columns: Option<Vec<String>>,
+    /// Inclusive ranges of Parquet row groups, or Arrow IPC record batches,
+    /// to read. They are read in file order, and the offset and limit count
+    /// rows within them. `None` reads every row group.
+    pub row_groups: Option<Vec<RangeInclusive<usize>>>,
+    /// Only return rows matching this filter. The offset and limit count
+    /// matching rows.
+    pub filter: Option<Filter>,
+}
+
+impl ReadOptions {
+    /// Combine `column_indices` and `columns` into one projection.
+    fn projection(&self, schema: &Schema) -> Result<Option<Projection>> {
+        let indices = match &self.column_indices {
+            Some(indices) => Some(Projection::from_indices(schema, indices)?),
+            None => None,
+        };
+        let names = match (&self.columns, &self.exclude_columns) {
+            (None, None) => None,
+            (columns, exclude) => {
+                let names = select_columns(
+                    schema,
+                    columns.as_deref(),
+                    exclude.as_deref().unwrap_or_default(),
+                )?;
+                Some(Projection::from_names(schema, &names)?)
+            }
+        };
+
+        Ok(match (indices, names) {
+            (Some(indices), Some(names)) => Some(indices.union(names)),
+            (projection, None) | (None, projection) => projection,
+        })
+    }
+}
+
+/// Streaming reader over the record batches of a Parquet or Arrow file.
+///
+/// Batches are decoded lazily as the iterator is advanced, so only the batch
+/// currently being processed is held in memory.
+pub struct BatchReader {
+    source: BatchSource,
+    schema: SchemaRef,
+    /// Rows still to be skipped before the first returned row.
+    skip: usize,
+    /// Rows still to be returned, `None` when unlimited.
+    remaining: Option<usize>,
+    /// Row filter applied to Arrow IPC batches before projection.
+    filter: Option<Filter>,
+    /// Columns and struct fields to extract from Arrow IPC batches.
+    projection: Option<Projection>,
+    /// Re-chunks Arrow IPC batches to the requested batch size.
+    coalescer: Option<BatchCoalescer>,
+}
+
+enum BatchSource {
+    Parquet(ParquetRecordBatchReader),
+    /// Arrow IPC file or stream batches, before projection and re-chunking.
+    Arrow(Box<dyn Iterator<Item = Result<RecordBatch>> + Send>),
+}
+
+/// Decodes the record batches of an Arrow IPC file from the blocks listed in
+/// its cached footer.
+struct ArrowBlockReader<R> {
+    reader: Arc<R>,
+    decoder: FileDecoder,
+    blocks: std::vec::IntoIter<Block>,
+}
+
+impl<R: ChunkReader> ArrowBlockReader<R> {
+    /// Decode the record batches at the given positions in the footer.
+    fn try_new(
+        reader: Arc<R>,
+        schema: SchemaRef,
+        footer: &ArrowFooter,
+        blocks: &[usize],
+    ) -> Result<Self> {
+        let mut decoder = FileDecoder::new(schema, footer.version);
+        for block in &footer.dictionaries {
+            let buf = read_ipc_block(&*reader, block)?;
+            decoder.read_dictionary(block, &buf)?;
+        }
+
+        Ok(Self {
+            reader,
+            decoder,
+            blocks: blocks
+                .iter()
+                .map(|&i| footer.record_batches[i])
+                .collect::<Vec<_>>()
+                .into_iter(),
+        })
+    }
+}
+
+impl<R: ChunkReader> Iterator for ArrowBlockReader<R> {
+    type Item = Result<RecordBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let block = self.blocks.next()?;
+            let batch = read_ipc_block(&*self.reader, &block)
+                .and_then(|buf| Ok(self.decoder.read_record_batch(&block, &buf)?));
+            match batch {
+                Ok(Some(batch)) => return Some(Ok(batch)),
+                Ok(None) => continue,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Read the message header and body of a block into one buffer.
+fn read_ipc_block<R: ChunkReader>(reader: &R, block: &Block) -> Result<Buffer> {
+    let (offset, length) = ipc_block_range(reader.len(), block, true)?;
+    Ok(Buffer::from(reader.get_bytes(offset, length)?))
+}
+
+/// Offset and length of a block, with or without its body, checked against
+/// the file size since corrupt footers can hold any value.
+fn ipc_block_range(file_size: u64, block: &Block, with_body: bool) -> Result<(u64, usize)> {
+    let invalid = || {
+        ArrowError::ParseError(format!(
+            "Invalid block at offset {} with metadata length {} and body length {}",
+            block.offset(),
+            block.metaDataLength(),
+            block.bodyLength()
+        ))
+    };
+    let offset = u64::try_from(block.offset()).map_err(|_| invalid())?;
+    let metadata_length = u64::try_from(block.metaDataLength()).map_err(|_| invalid())?;
+    let body_length = match with_body {
+        true => u64::try_from(block.bodyLength()).map_err(|_| invalid())?,
+        false => 0,
+    };
+    let length = metadata_length
+        .checked_add(body_length)
+        .filter(|length| {
+            offset
+                .checked_add(*length)
+                .is_some_and(|end| end <= file_size)
+        })
+        .ok_or_else(invalid)?;
+    Ok((offset, usize::try_from(length).map_err(|_| invalid())?))
+}
+
+impl BatchReader {
+    fn from_parquet(reader: ParquetRecordBatchReader, skip: usize, options: &ReadOptions) -> Self {
+        // Projection, filter and batch size are applied by the Parquet reader
+        Self {
+            schema: reader.schema(),
+            source: BatchSource::Parquet(reader),
+            skip,
+            remaining: options.limit,
+            filter: None,
+            projection: None,
+            coalescer: None,
+        }
+    }
+
+    fn from_arrow(
+        batches: Box<dyn Iterator<Item = Result<RecordBatch>> + Send>,
+        schema: SchemaRef,
+        options: &ReadOptions,
+    ) -> Result<Self> {
+        if let Some(filter) = &options.filter {
+            filter.column_indices(&schema)?;
+        }
+
+        // Arrow IPC batches are filtered and projected after they are read
+        let projection = options.projection(&schema)?;
+
+        // Create projected schema
+        let schema = match &projection {
+            Some(projection) => Arc::new(projection.project_schema(&schema)),
+            None => schema,
+        };
+
+        // Arrow IPC batches keep the size they were written with, so re-chunk
+        // them to match the batch size Parquet reads produce.
+        let coalescer = options
+            .batch_size
+            .map(|batch_size| BatchCoalescer::new(schema.clone(), batch_size));
+
+        Ok(Self {
+            source: BatchSource::Arrow(batches),
+            schema,
+            skip: options.offset,
+            remaining: options.limit,
+            filter: options.filter.clone(),
+            projection,
+            coalescer,
+        })
+    }
+
+    /// Schema of the batches produced by this reader, after projection.
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn next_batch(&mut self) -> Option<Result<RecordBatch>> {
+        let batch = match &mut self.source {
+            BatchSource::Parquet(reader) => reader.next()?.map_err(Into::into),
+            BatchSource::Arrow(reader) => reader.next()?,
+        };
+        let mut batch = match batch {
+            Ok(batch) => batch,
+            Err(e) => return Some(Err(e)),
+        };
+        if let Some(filter) = &self.filter {
+            let filtered = filter
+                .evaluate(&batch)
+                .and_then(|mask| Ok(filter_record_batch(&batch, &mask)?));
+            batch = match filtered {
+                Ok(batch) => batch,
+                Err(e) => return Some(Err(e)),
+            };
+        }
+        match &self.projection {
+            Some(projection) => Some(projection.project_batch(&batch, &self.schema)),
+            None => Some(Ok(batch)),
+        }
+    }
+}
+
+impl BatchReader {
+    /// Next batch trimmed to the requested row window, before re-chunking.
+    fn next_window_batch(&mut self) -> Option<Result<RecordBatch>> {
+        loop {
+            if self.remaining == Some(0) {
+                return None;
+            }
+
+            let mut batch = match self.next_batch()? {
+                Ok(batch) => batch,
+                Err(e) => return Some(Err(e)),
+            };
+
+            if self.skip > 0 {
+                let skipped = self.skip.min(batch.num_rows());
+                self.skip -= skipped;
+                batch = batch.slice(skipped, batch.num_rows() - skipped);
+            }
+            if let Some(remaining) = &mut self.remaining {
+                if batch.num_rows() > *remaining {
+                    batch = batch.slice(0, *remaining);
+                }
+                *remaining -= batch.num_rows();
+            }
+
+            if batch.num_rows() > 0 {
+                return Some(Ok(batch));
+            }
+        }
+    }
+}
+
+impl Iterator for BatchReader {
+    type Item = Result<RecordBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(coalescer) = &mut self.coalescer
+                && let Some(batch) = coalescer.next_completed_batch()
+            {
+                return Some(Ok(batch));
+            }
+
+            let batch = self.next_window_batch();
+            let Some(coalescer) = &mut self.coalescer else {
+                return batch;
+            };
+
+            let result = match batch {
+                Some(Ok(batch)) => coalescer.push_batch(batch),
+                Some(Err(e)) => return Some(Err(e)),
+                None if coalescer.get_buffered_rows() > 0 => coalescer.finish_buffered_batch(),
+                None => return None,
+            };
+            if let Err(e) = result {
+                return Some(Err(e.into()));
+            }
+        }
+    }
+}
+
+/// Validate the requested row groups, or select all `num_row_groups` of
+/// them, in file order.
+fn select_row_groups(
+    row_groups: Option<&[RangeInclusive<usize>]>,
+    num_row_groups: usize,
+) -> Result<Vec<usize>> {
+    let Some(row_groups) = row_groups else {
+        return Ok((0..num_row_groups).collect());
+    };
+    // Check the ranges before expanding them, so a range like `0-4000000000`
+    // fails instead of allocating
+    if let Some(range) = row_groups
+        .iter()
+        .find(|range| *range.end() >= num_row_groups)
+    {
+        return Err(ParquetViewerError::RowGroupOutOfRange {
+            index: (*range.start()).max(num_row_groups),
+            num_row_groups,
+        });
+    }
+
+    let mut row_groups: Vec<usize> = row_groups.iter().cloned().flatten().collect();
+    row_groups.sort_unstable();
+    row_groups.dedup();
+    Ok(row_groups)
+}
+
+/// Restrict a Parquet reader to the rows in `offset..offset + limit` of the
+/// given row groups.
+///
+/// Row groups entirely outside the window are skipped using their row counts
+/// from the footer, and a `RowSelection` trims the boundary row groups.
+fn select_parquet_rows<T: ChunkReader + 'static>(
+    builder: ParquetRecordBatchReaderBuilder<T>,
+    row_groups: Vec<usize>,
+    offset: usize,
+    limit: Option<usize>,
+) -> ParquetRecordBatchReaderBuilder<T> {
+    if offset == 0 && limit.is_none() {
+        return builder.with_row_groups(row_groups);
+    }
+
+    let end = limit.map_or(usize::MAX, |limit| offset.saturating_add(limit));
+    let mut selected_row_groups = Vec::new();
+    let mut selectors = Vec::new();
+    let mut first_row = 0usize;
+    for idx in row_groups {
+        let num_rows = builder.metadata().row_group(idx).num_rows() as usize;
+        let last_row = first_row + num_rows;
+        if last_row > offset && first_row < end {
+            let skip = offset.saturating_sub(first_row);
+            let select = end.min(last_row) - first_row - skip;
+            selectors.push(RowSelector::skip(skip));
+            selectors.push(RowSelector::select(select));
+            selectors.push(RowSelector::skip(num_rows - skip - select));
+            selected_row_groups.push(idx);
+        }
+        first_row = last_row;
+    }
+
+    builder
+        .with_row_groups(selected_row_groups)
+        .with_row_selection(RowSelection::from(selectors))
+}
+
+/// Skip the row groups and pages whose statistics rule out `filter`, and
+/// evaluate it on the remaining rows while decoding.
+fn filter_parquet_rows<T: ChunkReader + 'static>(
+    builder: ParquetRecordBatchReaderBuilder<T>,
+    mut row_groups: Vec<usize>,
+    filter: &Filter,
+) -> Result<ParquetRecordBatchReaderBuilder<T>> {
+    let column_indices = filter.column_indices(builder.schema())?;
+    let total_row_groups = row_groups.len();
+    let matching = filter.prune_row_groups(builder.metadata(), builder.schema())?;
+    row_groups.retain(|idx| matching.contains(idx));
+    log::debug!(
+        "Filter keeps {} of {} row groups",
+        row_groups.len(),
+        total_row_groups
+    );
+    let selection = filter.select_pages(builder.metadata(), builder.schema(), &row_groups)?;
+
+    // Only the columns referenced by the filter are decoded to evaluate it
+    let mask = ProjectionMask::roots(builder.parquet_schema(), column_indices);
+    let filter = filter.clone();
+    let predicate = ArrowPredicateFn::new(mask, move |batch| {
+        filter
+            .evaluate(&batch)
+            .map_err(|e| ArrowError::ExternalError(Box::new(e)))
+    });
+
+    let mut builder = builder
+        .with_row_groups(row_groups)
+        .with_row_filter(RowFilter::new(vec![Box::new(predicate)]));
+    if let Some(selection) = selection {
+        log::debug!(
+            "Page index selects {} of {} rows",
+            selection.row_count(),
+            selection.row_count() + selection.skipped_row_count()
+        );
+        builder = builder.with_row_selection(selection);
+    }
+    Ok(builder)
+}
+
+/// Open a streaming reader over all columns of a file.
+pub fn read_batches(
+    file_path: &Path,
+    batch_size: Option<usize>,
+    limit: Option<usize>,
+) -> Result<BatchReader> {
+    let options = ReadOptions {
+        batch_size,
+        limit,
+        ..Default::default()
+    };
+    ParquetViewerFile::open(file_path)?.read_batches(&options)
+}
+
+/// Open a streaming reader over the given root columns of a file.
+pub fn read_batches_with_projection(
+    file_path: &Path,
+    column_indices: Vec<usize>,
+    batch_size: Option<usize>,
+    limit: Option<usize>,
+) -> Result<BatchReader> {
+    let options = ReadOptions {
+        batch_size,
+        limit,
+        column_indices: Some(column_indices),
+        ..Default::default()
+    };
+    ParquetViewerFile::open(file_path)?.read_batches(&options)
+}
+
+/// Open a streaming reader over the named columns of a file, see
+/// [`ReadOptions::columns`].
+pub fn read_batches_with_columns(
+    file_path: &Path,
+    columns: Vec<String>,
+    batch_size: Option<usize>,
+    limit: Option<usize>,
+) -> Result<BatchReader> {
+    let options = ReadOptions {
+        batch_size,
+        limit,
+        columns: Some(columns),
+        ..Default::default()
+    };
+    ParquetViewerFile::open(file_path)?.read_batches(&options)
+}
+
+/// Open a streaming reader configured by [`ReadOptions`].
+pub fn read_batches_with_options(file_path: &Path, options: &ReadOptions) -> Result<BatchReader> {
+    ParquetViewerFile::open(file_path)?.read_batches(options)
+}
+
+/// Open a streaming reader over an in-memory buffer such as [`Bytes`], or any
+/// other [`ChunkReader`].
+pub fn read_batches_from_reader<R: ChunkReader + 'static>(
+    reader: R,
+    options: &ReadOptions,
+) -> Result<BatchReader> {
+    ParquetViewerFile::try_new(reader)?.read_batches(options)
+}
+
+/// Open a streaming reader over an Arrow IPC stream from a non-seekable
+/// source such as stdin.
+///
+/// Messages are decoded incrementally as the reader is advanced. Streams
+/// have no footer to check [`ReadOptions::row_groups`] against, so record
+/// batches past the end of the stream are silently ignored.
+pub fn read_ipc_stream<R: Read + Send + 'static>(
+    reader: R,
+    options: &ReadOptions,
+) -> Result<BatchReader> {
+    let reader = ArrowStreamReader::try_new_buffered(reader, None)?;
+    let schema = reader.schema();
+    let batches = reader.map(|batch| batch.map_err(Into::into));
+
+    let Some(row_groups) = &options.row_groups else {
+        return BatchReader::from_arrow(Box::new(batches), schema, options);
+    };
+    // Record batches have to be decoded in order, but stop after the last
+    // requested one
+    let last = row_groups
+        .iter()
+        .map(|range| range.end().saturating_add(1))
+        .max()
+        .unwrap_or(0);
+    let row_groups = row_groups.clone();
+    let batches = batches
+        .take(last)
+        .enumerate()
+        .filter(move |(idx, _)| row_groups.iter().any(|range| range.contains(idx)))
+        .map(|(_, batch)| batch);
+    BatchReader::from_arrow(Box::new(batches), schema, options)
+}
+
+pub fn read_data(
+    file_path: &Path,
+    batch_size: Option<usize>,
+    limit: Option<usize>,
+) -> Result<Vec<RecordBatch>> {
+    read_batches(file_path, batch_size, limit)?.collect()
+}
+
+pub fn read_data_with_projection(
+    file_path: &Path,
+    column_indices: Vec<usize>,
+    batch_size: Option<usize>,
+    limit: Option<usize>,
+) -> Result<Vec<RecordBatch>> {
+    read_batches_with_projection(file_path, column_indices, batch_size, limit)?.collect()
+}
+
+pub fn read_data_with_columns(
+    file_path: &Path,
+    columns: Vec<String>,
+    batch_size: Option<usize>,
+    limit: Option<usize>,
+) -> Result<Vec<RecordBatch>> {
+    read_batches_with_columns(file_path, columns, batch_size, limit)?.collect()
+}
+
+pub fn read_data_with_options(file_path: &Path, options: &ReadOptions) -> Result<Vec<RecordBatch>> {
+    read_batches_with_options(file_path, options)?.collect()
+}
+
+pub fn read_data_from_reader<R: ChunkReader + 'static>(
+    reader: R,
+    options: &ReadOptions,
+) -> Result<Vec<RecordBatch>> {
+    read_batches_from_reader(reader, options)?.collect()
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SqlFormatStyle {
+    Minimal,
+    Beautify,
+}
+
+pub fn sql_format(sql: &str, style: SqlFormatStyle) -> Result<String> {
+    let dialect = PostgreSqlDialect {};
+    let ast = Parser::parse_sql(&dialect, sql)?;
+
+    if ast.is_empty() {
+        return Ok(sql.to_string());
+    }
+
+    let sql = match style {
+        SqlFormatStyle::Minimal => {
+            // For minimal format
+            ast.iter().map(|stmt| stmt.to_string()).collect::<Vec<_>>()
+        }
+        SqlFormatStyle::Beautify => {
+            // For beautify format, see https://docs.rs/sqlparser/latest/sqlparser/#pretty-printing
+            ast.iter()
+                .map(|stmt| format!("{:#}", stmt))
+                .collect::<Vec<_>>()
+        }
+    };
+    Ok(sql.join(";\n\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{
+        ArrayRef, AsArray, Float32Array, Int8Array, Int32Array, ListBuilder, StringArray,
+        StringBuilder, StructArray, UInt32Array,
+    };
+    use arrow::datatypes::{DataType, Field, Fields, Schema};
+    use arrow::ipc::writer::{FileWriter as ArrowFileWriter, StreamWriter as ArrowStreamWriter};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::metadata::ColumnChunkMetaDataBuilder;
+    use parquet::file::properties::{
+        DEFAULT_MAX_ROW_GROUP_SIZE, EnabledStatistics, WriterProperties, WriterVersion,
+    };
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use tempfile::NamedTempFile;
+
+    fn create_test_parquet_file() -> NamedTempFile {
+        let temp_file = NamedTempFile::new().unwrap();
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+
+        let id_array = Int32Array::from(vec![1, 2, 3, 4, 5]);
+        let name_array = StringArray::from(vec!["Alice", "Bob", "Charlie", "David", "Eve"]);
+
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(id_array), Arc::new(name_array)],
+        )
+        .unwrap();
+
+        let file = temp_file.reopen().unwrap();
+        let mut writer = ArrowWriter::try_new(file, schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        temp_file
+    }
+
+    #[test]
+    fn test_read_schema() {
+        let temp_file = create_test_parquet_file();
+        let schema = read_schema(temp_file.path()).unwrap();
+
+        assert_eq!(schema.fields().len(), 2);
+        assert_eq!(schema.field(0).name(), "id");
+        assert_eq!(schema.field(1).name(), "name");
+        assert_eq!(schema.field(0).data_type(), &DataType::Int32);
+        assert_eq!(schema.field(1).data_type(), &DataType::Utf8);
+    }
+
+    #[test]
+    fn test_read_metadata() {
+        let temp_file = create_test_parquet_file();
+        let metadata = read_metadata(temp_file.path()).unwrap();
+
+        assert_eq!(metadata.total_records, 5);
+        assert_eq!(metadata.total_fields, 2);
+        assert_eq!(metadata.total_row_groups, 1);
+        assert!(metadata.file_size > 0);
+    }
+
+    #[test]
+    fn test_read_data() {
+        let temp_file = create_test_parquet_file();
+        let batches = read_data(temp_file.path(), None, None).unwrap();
+
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 5);
+        assert_eq!(batch.num_columns(), 2);
+    }
+
+    #[test]
+    fn test_read_data_with_projection() {
+        let temp_file = create_test_parquet_file();
+        let batches = read_data_with_projection(temp_file.path(), vec![1], None, None).unwrap();
+
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 5);
+        assert_eq!(batch.num_columns(), 1);
+        assert_eq!(batch.schema().field(0).name(), "name");
+    }
+
+    #[test]
+    fn test_read_batches_streaming() {
+        let temp_file = create_test_parquet_file();
+        let reader = read_batches(temp_file.path(), Some(2), None).unwrap();
+        assert_eq!(reader.schema().fields().len(), 2);
+
+        let rows: Vec<usize> = reader.map(|batch| batch.unwrap().num_rows()).collect();
+        assert_eq!(rows, vec![2, 2, 1]);
+    }
+
+    #[test]
+    fn test_read_batches_stops_at_limit() {
+        let temp_file = create_test_parquet_file();
+        let reader = read_batches(temp_file.path(), Some(2), Some(3)).unwrap();
+
+        assert_eq!(reader.count(), 2);
+    }
+
+    fn create_multi_row_group_parquet_file() -> NamedTempFile {
+        let temp_file = NamedTempFile::new().unwrap();
+
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+        let id_array = Int32Array::from((0..100).collect::<Vec<i32>>());
+        let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(id_array)]).unwrap();
+
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(10)
+            .build();
+        let file = temp_file.reopen().unwrap();
+        let mut writer = ArrowWriter::try_new(file, schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        temp_file
+    }
+
+    /// Replace the footer of the Parquet file in `bytes`, passing the
+    /// metadata of every column chunk through `rewrite` with the index of
+    /// its row group.
+    fn rewrite_column_chunks(
+        bytes: &mut Vec<u8>,
+        rewrite: impl Fn(usize, ColumnChunkMetaDataBuilder) -> ColumnChunkMetaDataBuilder,
+    ) {
+        use parquet::file::metadata::{ParquetMetaDataReader, ParquetMetaDataWriter};
+
+        let metadata = ParquetMetaDataReader::new()
+            .parse_and_finish(&Bytes::from(bytes.clone()))
+            .unwrap();
+        let row_groups = metadata
+            .row_groups()
+            .iter()
+            .enumerate()
+            .map(|(index, row_group)| {
+                let columns = row_group
+                    .columns()
+                    .iter()
+                    .map(|column| {
+                        rewrite(index, column.clone().into_builder())
+                            .build()
+                            .unwrap()
+                    })
+                    .collect();
+                row_group
+                    .clone()
+                    .into_builder()
+                    .set_column_metadata(columns)
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+        let metadata = metadata.into_builder().set_row_groups(row_groups).build();
+
+        let footer_length = u32::from_le_bytes(bytes[bytes.len() - 8..][..4].try_into().unwrap());
+        bytes.truncate(bytes.len() - 8 - footer_length as usize);
+        ParquetMetaDataWriter::new(&mut *bytes, &metadata)
+            .finish()
+            .unwrap();
+    }
+
+    /// The first half of the row groups of a file followed by its original
+    /// footer, whose later column chunks point past the end of the data.
+    fn truncate_keeping_footer(bytes: &[u8]) -> Bytes {
+        let footer_length = u32::from_le_bytes(bytes[bytes.len() - 8..][..4].try_into().unwrap());
+        let footer_start = bytes.len() - 8 - footer_length as usize;
+        let mut truncated = bytes[..footer_start / 2].to_vec();
+        truncated.extend_from_slice(&bytes[footer_start..]);
+        Bytes::from(truncated)
+    }
+
+    fn collect_ids(batches: &[RecordBatch]) -> Vec<i32> {
+        batches
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int32Array>()
+                    .unwrap()
+                    .values()
+                    .to_vec()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_read_data_limit_is_exact() {
+        let temp_file = create_test_parquet_file();
+        let batches = read_data(temp_file.path(), Some(2), Some(3)).unwrap();
+
+        let total_rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(total_rows, 3);
+    }
+
+    #[test]
+    fn test_read_data_with_offset_window() {
+        let temp_file = create_multi_row_group_parquet_file();
+        let options = ReadOptions {
+            batch_size: Some(4),
+            offset: 25,
+            limit: Some(12),
+            ..Default::default()
+        };
+        let batches = read_data_with_options(temp_file.path(), &options).unwrap();
+
+        assert_eq!(collect_ids(&batches), (25..37).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_read_data_offset_past_end() {
+        let temp_file = create_multi_row_group_parquet_file();
+        let options = ReadOptions {
+            offset: 95,
+            limit: Some(10),
+            ..Default::default()
+        };
+        let batches = read_data_with_options(temp_file.path(), &options).unwrap();
+        assert_eq!(collect_ids(&batches), (95..100).collect::<Vec<_>>());
+
+        let options = ReadOptions {
+            offset: 200,
+            ..Default::default()
+        };
+        let batches = read_data_with_options(temp_file.path(), &options).unwrap();
+        assert!(batches.is_empty());
+    }
+
+    #[test]
+    fn test_filter_prunes_row_groups() {
+        let temp_file = create_multi_row_group_parquet_file();
+        let metadata =
+            ArrowReaderMetadata::load(&temp_file.reopen().unwrap(), Default::default()).unwrap();
+
+        let filter = Filter::parse("id >= 95 OR id IN (3, 4)").unwrap();
+        let row_groups = filter
+            .prune_row_groups(metadata.metadata(), metadata.schema())
+            .unwrap();
+        assert_eq!(row_groups, vec![0, 9]);
+
+        let options = ReadOptions {
+            filter: Some(filter),
+            ..Default::default()
+        };
+        let batches = read_data_with_options(temp_file.path(), &options).unwrap();
+        assert_eq!(collect_ids(&batches), vec![3, 4, 95, 96, 97, 98, 99]);
+    }
+
+    #[test]
+    fn test_read_data_with_filter_offset_window() {
+        let temp_file = create_multi_row_group_parquet_file();
+        let options = ReadOptions {
+            batch_size: Some(2),
+            offset: 5,
+            limit: Some(3),
+            filter: Some(Filter::parse("id >= 50 AND id <> 52").unwrap()),
+            ..Default::default()
+        };
+        let batches = read_data_with_options(temp_file.path(), &options).unwrap();
+        assert_eq!(collect_ids(&batches), vec![56, 57, 58]);
+    }
+
+    #[test]
+    fn test_filter_skips_pages_with_page_index() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+        let id_array = Int32Array::from((0..100).collect::<Vec<i32>>());
+        let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(id_array)]).unwrap();
+
+        let props = WriterProperties::builder()
+            .set_write_batch_size(10)
+            .set_data_page_row_count_limit(10)
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(temp_file.reopen().unwrap(), schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let file = ParquetViewerFile::open(temp_file.path()).unwrap();
+        let metadata = file.metadata().unwrap();
+        assert!(metadata.has_column_index);
+        assert!(metadata.has_offset_index);
+
+        let Footer::Parquet(footer) = &file.footer else {
+            panic!("expected a Parquet footer");
+        };
+        let filter = Filter::parse("id < 5 OR id >= 95").unwrap();
+        let selection = filter
+            .select_pages(footer.metadata(), footer.schema(), &[0])
+            .unwrap()
+            .unwrap();
+        assert_eq!(selection.row_count(), 20);
+
+        let options = ReadOptions {
+            filter: Some(filter),
+            ..Default::default()
+        };
+        let batches = file.read_data(&options).unwrap();
+        assert_eq!(
+            collect_ids(&batches),
+            vec![0, 1, 2, 3, 4, 95, 96, 97, 98, 99]
+        );
+    }
+
+    #[test]
+    fn test_filter_literals_out_of_column_range() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("count", DataType::UInt32, true),
+            Field::new("small", DataType::Int8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![0, 1, 2, 3])),
+                Arc::new(UInt32Array::from(vec![
+                    Some(0),
+                    None,
+                    Some(7),
+                    Some(u32::MAX),
+                ])),
+                Arc::new(Int8Array::from(vec![-128, 0, 5, 127])),
+            ],
+        )
+        .unwrap();
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(temp_file.reopen().unwrap(), schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let metadata =
+            ArrowReaderMetadata::load(&temp_file.reopen().unwrap(), Default::default()).unwrap();
+        for (expr, ids) in [
+            ("count > -1", vec![0, 2, 3]),
+            ("count <> -1", vec![0, 2, 3]),
+            ("count <= -1", vec![]),
+            ("count = -5", vec![]),
+            ("count >= 4294967295", vec![3]),
+            ("count < 4294967296", vec![0, 2, 3]),
+            ("small < 300", vec![0, 1, 2, 3]),
+            ("small > -300", vec![0, 1, 2, 3]),
+            ("small >= 300", vec![]),
+            ("small = -128", vec![0]),
+        ] {
+            let filter = Filter::parse(expr).unwrap();
+            let row_groups = filter
+                .prune_row_groups(metadata.metadata(), metadata.schema())
+                .unwrap();
+            let expected_row_groups = ids.iter().map(|id| *id as usize / 2).fold(
+                Vec::new(),
+                |mut row_groups, row_group| {
+                    if !row_groups.contains(&row_group) {
+                        row_groups.push(row_group);
+                    }
+                    row_groups
+                },
+            );
+            assert_eq!(row_groups, expected_row_groups, "{expr}");
+
+            let options = ReadOptions {
+                filter: Some(filter),
+                ..Default::default()
+            };
+            let batches = read_data_with_options(temp_file.path(), &options).unwrap();
+            assert_eq!(collect_ids(&batches), ids, "{expr}");
+        }
+    }
+
+    #[test]
+    fn test_filter_errors() {
+        assert!(matches!(
+            Filter::parse("id + 1 > 2"),
+            Err(ParquetViewerError::InvalidFilter(_))
+        ));
+        assert!(matches!(
+            Filter::parse("id = NULL"),
+            Err(ParquetViewerError::InvalidFilter(_))
+        ));
+
+        let temp_file = create_multi_row_group_parquet_file();
+        let options = ReadOptions {
+            filter: Some(Filter::parse("missing IS NULL").unwrap()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            read_batches_with_options(temp_file.path(), &options),
+            Err(ParquetViewerError::UnknownColumn { .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_detailed_metadata() {
+        let temp_file = create_multi_row_group_parquet_file();
+        let metadata = read_detailed_metadata(temp_file.path()).unwrap();
+
+        let row_groups = metadata.row_groups.unwrap();
+        assert_eq!(row_groups.len(), 10);
+        assert!(row_groups.iter().all(|rg| rg.num_rows == 10));
+
+        let column = &row_groups[3].columns[0];
+        assert_eq!(column.path, "id");
+        assert_eq!(column.physical_type, "INT32");
+        assert_eq!(column.num_values, 10);
+        assert!(column.compressed_size > 0);
+
+        let statistics = column.statistics.as_ref().unwrap();
+        assert_eq!(statistics.min.as_deref(), Some("30"));
+        assert_eq!(statistics.max.as_deref(), Some("39"));
+        assert_eq!(statistics.null_count, Some(0));
+
+        let metadata = read_detailed_metadata(create_test_arrow_file().path()).unwrap();
+        assert!(metadata.row_groups.is_none());
+
+        let metadata = read_metadata(temp_file.path()).unwrap();
+        assert!(metadata.row_groups.is_none());
+    }
+
+    #[test]
+    fn test_read_detailed_metadata_invalid_sorting_columns() {
+        use parquet::format::SortingColumn;
+
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+        )
+        .unwrap();
+        let sorting_column = |column_idx| SortingColumn {
+            column_idx,
+            descending: false,
+            nulls_first: true,
+        };
+        let props = WriterProperties::builder()
+            .set_sorting_columns(Some(vec![
+                sorting_column(0),
+                sorting_column(5),
+                sorting_column(-1),
+            ]))
+            .build();
+        let mut bytes = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut bytes, schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let metadata = read_detailed_metadata_from_reader(Bytes::from(bytes)).unwrap();
+        let sorting_columns = metadata.row_groups.unwrap()[0]
+            .sorting_columns
+            .clone()
+            .unwrap();
+        let columns: Vec<_> = sorting_columns.iter().map(|c| c.column.as_str()).collect();
+        assert_eq!(columns, vec!["id", "5", "-1"]);
+    }
+
+    #[test]
+    fn test_profile_data() {
+        let temp_file = create_test_parquet_file();
+        let profiles = profile_data(temp_file.path(), &ReadOptions::default()).unwrap();
+        assert_eq!(profiles.len(), 2);
+
+        let id = &profiles[0];
+        assert_eq!(id.name, "id");
+        assert_eq!(id.row_count, 5);
+        assert_eq!(id.null_count, 0);
+        assert_eq!(id.min.as_deref(), Some("1"));
+        assert_eq!(id.max.as_deref(), Some("5"));
+        assert_eq!(id.approx_distinct, Some(5));
+        let values = id.values.as_ref().unwrap();
+        assert_eq!(values.mean, 3.0);
+        assert!((values.stddev.unwrap() - 2.5f64.sqrt()).abs() < 1e-9);
+        assert_eq!(values.quantiles, vec![(0.25, 2.0), (0.5, 3.0), (0.75, 4.0)]);
+        assert!(id.lengths.is_none());
+
+        let name = &profiles[1];
+        assert_eq!(name.min.as_deref(), Some("Alice"));
+        assert_eq!(name.max.as_deref(), Some("Eve"));
+        assert!(name.values.is_none());
+        let lengths = name.lengths.as_ref().unwrap();
+        assert_eq!((lengths.min, lengths.max), (3.0, 7.0));
+
+        // Struct fields are profiled one by one, and options select the rows
+        let batch = create_nested_batch();
+        let arrow_file = NamedTempFile::new().unwrap();
+        let mut writer =
+            ArrowFileWriter::try_new(arrow_file.reopen().unwrap(), &batch.schema()).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+
+        let options = ReadOptions {
+            filter: Some(Filter::parse("id > 1").unwrap()),
+            ..Default::default()
+        };
+        let profiles = profile_data(arrow_file.path(), &options).unwrap();
+        let names: Vec<_> = profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "id",
+                "payload.user.id",
+                "payload.user.name",
+                "payload.score"
+            ]
+        );
+        assert_eq!(profiles[1].row_count, 2);
+        assert_eq!(profiles[1].min.as_deref(), Some("20"));
+    }
+
+    #[test]
+    fn test_top_values() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("host", DataType::Utf8, true),
+        ]));
+        let hosts = ["a", "b", "b", "c", "c", "c"];
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from((0..60).collect::<Vec<_>>())),
+                Arc::new(StringArray::from_iter(
+                    (0..60).map(|i| (i % 10 < 6).then(|| hosts[i % 10])),
+                )),
+            ],
+        )
+        .unwrap();
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(25)
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(temp_file.reopen().unwrap(), schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let counts = top_values(temp_file.path(), "host", 2, &ReadOptions::default()).unwrap();
+        assert_eq!(
+            counts.values,
+            vec![
+                ValueCount {
+                    value: "c".to_string(),
+                    count: 18
+                },
+                ValueCount {
+                    value: "b".to_string(),
+                    count: 12
+                },
+            ]
+        );
+        assert_eq!((counts.row_count, counts.null_count), (60, 24));
+        assert!(counts.is_exact());
+
+        let options = ReadOptions {
+            filter: Some(Filter::parse("id < 10").unwrap()),
+            ..Default::default()
+        };
+        let counts = top_values(temp_file.path(), "host", 10, &options).unwrap();
+        let values: Vec<_> = counts
+            .values
+            .iter()
+            .map(|v| (v.value.as_str(), v.count))
+            .collect();
+        assert_eq!(values, [("c", 3), ("b", 2), ("a", 1)]);
+
+        // Nested fields are counted by their dotted path
+        let batch = create_nested_batch();
+        let arrow_file = NamedTempFile::new().unwrap();
+        let mut writer =
+            ArrowFileWriter::try_new(arrow_file.reopen().unwrap(), &batch.schema()).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+        let counts = top_values(
+            arrow_file.path(),
+            "payload.user.name",
+            1,
+            &ReadOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(counts.values[0].value, "a");
+
+        // Too many distinct values to count exactly, heavy hitters remain
+        let ids = (0..250_000).chain(std::iter::repeat_n(7, 5_000));
+        let batch = RecordBatch::try_from_iter(vec![(
+            "id",
+            Arc::new(Int32Array::from_iter_values(ids)) as ArrayRef,
+        )])
+        .unwrap();
+        let arrow_file = NamedTempFile::new().unwrap();
+        let mut writer =
+            ArrowFileWriter::try_new(arrow_file.reopen().unwrap(), &batch.schema()).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+        let counts = top_values(arrow_file.path(), "id", 1, &ReadOptions::default()).unwrap();
+        assert!(!counts.is_exact());
+        assert_eq!(counts.values[0].value, "7");
+        assert!(counts.values[0].count + counts.max_error >= 5_001);
+        assert!(counts.values[0].count <= 5_001);
+    }
+
+    #[test]
+    fn test_top_values_dictionary_encoded() {
+        let batch = create_nested_batch();
+        let write = |props: WriterProperties| {
+            let mut bytes = Vec::new();
+            let mut writer = ArrowWriter::try_new(&mut bytes, batch.schema(), Some(props)).unwrap();
+            writer.write(&batch).unwrap();
+            writer.close().unwrap();
+            ParquetViewerFile::try_new(Bytes::from(bytes)).unwrap()
+        };
+        let leaf_type = |metadata: &ArrowReaderMetadata| {
+            let DataType::Struct(payload) = metadata.schema().field(1).data_type() else {
+                panic!("payload is a struct");
+            };
+            let DataType::Struct(user) = payload[0].data_type() else {
+                panic!("payload.user is a struct");
+            };
+            user[1].data_type().clone()
+        };
+
+        // A nested dictionary encoded leaf is read as keys into its dictionary
+        let file = write(WriterProperties::default());
+        let Footer::Parquet(metadata) = &file.footer else {
+            panic!("a Parquet footer");
+        };
+        let dictionary = dictionary_metadata(metadata, "payload.user.name")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            leaf_type(&dictionary),
+            DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8))
+        );
+        assert!(dictionary_metadata(metadata, "id").unwrap().is_none());
+        let counts = file
+            .top_values("payload.user.name", 1, &ReadOptions::default())
+            .unwrap();
+        assert_eq!(counts.values[0].value, "a");
+        assert_eq!(counts.row_count, 3);
+
+        // Without a dictionary page the values are read as they are
+        let file = write(
+            WriterProperties::builder()
+                .set_dictionary_enabled(false)
+                .build(),
+        );
+        let Footer::Parquet(metadata) = &file.footer else {
+            panic!("a Parquet footer");
+        };
+        assert!(
+            dictionary_metadata(metadata, "payload.user.name")
+                .unwrap()
+                .is_none()
+        );
+        let counts = file
+            .top_values("payload.user.name", 1, &ReadOptions::default())
+            .unwrap();
+        assert_eq!(counts.values[0].value, "a");
+    }
+
+    #[test]
+    fn test_read_schema_tree() {
+        let id = Field::new("id", DataType::Int64, false).with_metadata(HashMap::from([
+            ("PARQUET:field_id".to_string(), "1".to_string()),
+            ("comment".to_string(), "primary key".to_string()),
+        ]));
+        let tags = Field::new_list("tags", Field::new_list_field(DataType::Utf8, true), true);
+        let price = Field::new("price", DataType::Decimal128(10, 2), true);
+        let schema = Arc::new(Schema::new(vec![id, tags, price]));
+        let batch = RecordBatch::new_empty(schema.clone());
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut writer = ArrowWriter::try_new(temp_file.reopen().unwrap(), schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let tree = read_schema_tree(temp_file.path()).unwrap();
+        assert_eq!(tree.children.len(), 3);
+
+        let id = &tree.children[0];
+        assert_eq!(id.repetition.as_deref(), Some("REQUIRED"));
+        assert_eq!(id.physical_type.as_deref(), Some("INT64"));
+        assert_eq!(id.arrow_type.as_deref(), Some("Int64"));
+        assert_eq!(id.field_id, Some(1));
+        assert_eq!(
+            id.metadata,
+            vec![("comment".to_string(), "primary key".to_string())]
+        );
+
+        // Three level list: the repeated group has no Arrow field of its own
+        let tags = &tree.children[1];
+        assert_eq!(tags.logical_type.as_deref(), Some("LIST"));
+        assert_eq!(tags.arrow_type.as_deref(), Some("List"));
+        let list = &tags.children[0];
+        assert_eq!(list.repetition.as_deref(), Some("REPEATED"));
+        assert_eq!(list.arrow_type, None);
+        let item = &list.children[0];
+        assert_eq!(item.logical_type.as_deref(), Some("STRING"));
+        assert_eq!(item.arrow_type.as_deref(), Some("Utf8"));
+
+        let price = &tree.children[2];
+        assert_eq!(price.logical_type.as_deref(), Some("DECIMAL(10,2)"));
+        assert_eq!(price.converted_type.as_deref(), Some("DECIMAL"));
+        assert_eq!(price.arrow_type.as_deref(), Some("Decimal128(10, 2)"));
+
+        let tree = read_schema_tree(create_test_arrow_file().path()).unwrap();
+        assert_eq!(tree.children[1].name, "name");
+        assert_eq!(tree.children[1].physical_type, None);
+        assert_eq!(tree.children[1].arrow_type.as_deref(), Some("Utf8"));
+    }
+
+    #[test]
+    fn test_column_statistics() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("count", DataType::UInt32, true),
+            Field::new("name", DataType::Utf8, false),
+            Field::new("note", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![-5, 3, 7, -20])),
+                Arc::new(UInt32Array::from(vec![Some(1), None, Some(u32::MAX), None])),
+                Arc::new(StringArray::from(vec!["banana", "apple", "cherry", "date"])),
+                Arc::new(StringArray::from(vec![
+                    Some("a"),
+                    None,
+                    Some("b"),
+                    Some("c"),
+                ])),
+            ],
+        )
+        .unwrap();
+
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .set_statistics_truncate_length(Some(4))
+            .set_column_statistics_enabled("note".into(), EnabledStatistics::None)
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(temp_file.reopen().unwrap(), schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let statistics = read_column_statistics(temp_file.path()).unwrap();
+        assert_eq!(statistics.len(), 4);
+
+        let id = &statistics[0];
+        assert_eq!(id.path, "id");
+        assert_eq!(id.min.as_deref(), Some("-20"));
+        assert_eq!(id.max.as_deref(), Some("7"));
+        assert!(id.is_min_exact && id.is_max_exact);
+        assert_eq!(id.null_count, Some(0));
+        assert_eq!(id.num_row_groups, 2);
+        assert!(id.issues.is_empty());
+
+        // Unsigned columns are compared as unsigned values
+        let count = &statistics[1];
+        assert_eq!(count.min.as_deref(), Some("1"));
+        assert_eq!(count.max.as_deref(), Some(u32::MAX.to_string().as_str()));
+        assert_eq!(count.null_count, Some(2));
+        assert!(count.issues.is_empty());
+
+        let name = &statistics[2];
+        assert_eq!(name.min.as_deref(), Some("appl"));
+        assert!(!name.is_min_exact);
+        assert!(
+            name.issues
+                .contains(&StatisticsIssue::Inexact { row_groups: 2 })
+        );
+
+        let note = &statistics[3];
+        assert_eq!(note.min, None);
+        assert_eq!(note.null_count, None);
+        assert_eq!(
+            note.issues,
+            vec![StatisticsIssue::Missing { row_groups: 2 }]
+        );
+
+        let error = read_column_statistics(create_test_arrow_file().path()).unwrap_err();
+        assert!(matches!(error, ParquetViewerError::ParquetRequired(_)));
+    }
+
+    #[test]
+    fn test_column_statistics_byte_array_decimals() {
+        use parquet::data_type::{ByteArray, ByteArrayType};
+        use parquet::file::writer::SerializedFileWriter;
+        use parquet::schema::parser::parse_message_type;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let schema =
+            parse_message_type("message test { required binary amount (DECIMAL(4,2)); }").unwrap();
+        let mut writer = SerializedFileWriter::new(
+            temp_file.reopen().unwrap(),
+            Arc::new(schema),
+            Default::default(),
+        )
+        .unwrap();
+        // Big-endian two's complement, -1 sorts after 3 when compared as bytes
+        for values in [[-1i8, 5], [3, 4]] {
+            let values: Vec<ByteArray> = values
+                .iter()
+                .map(|v| ByteArray::from(vec![*v as u8]))
+                .collect();
+            let mut row_group = writer.next_row_group().unwrap();
+            let mut column = row_group.next_column().unwrap().unwrap();
+            column
+                .typed::<ByteArrayType>()
+                .write_batch(&values, None, None)
+                .unwrap();
+            column.close().unwrap();
+            row_group.close().unwrap();
+        }
+        writer.close().unwrap();
+
+        let statistics = read_column_statistics(temp_file.path()).unwrap();
+        assert_eq!(statistics[0].min.as_deref(), Some("ff"));
+        assert!(statistics[0].is_min_exact);
+        assert!(statistics[0].issues.is_empty());
+    }
+
+    #[test]
+    fn test_column_statistics_short_float16() {
+        use parquet::data_type::FixedLenByteArray;
+        use parquet::file::statistics::Statistics;
+
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "value",
+            DataType::Float16,
+            false,
+        )]));
+        let values = Float32Array::from(vec![1.0, 2.0]);
+        let values = arrow::compute::cast(&values, &DataType::Float16).unwrap();
+        let batch = RecordBatch::try_new(schema.clone(), vec![values]).unwrap();
+        let mut bytes = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut bytes, schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        // A min of a single byte cannot be decoded as a Float16
+        rewrite_column_chunks(&mut bytes, |_, column| {
+            column.set_statistics(Statistics::new(
+                Some(FixedLenByteArray::from(vec![0x3c])),
+                // 2.0 as a little-endian Float16
+                Some(FixedLenByteArray::from(vec![0x00, 0x40])),
+                None,
+                Some(0),
+                false,
+            ))
+        });
+
+        let statistics = read_column_statistics_from_reader(Bytes::from(bytes)).unwrap();
+        assert_eq!(statistics[0].min, None);
+        assert_eq!(statistics[0].max.as_deref(), Some("2"));
+        assert!(!statistics[0].is_max_exact);
+        assert_eq!(
+            statistics[0].issues,
+            vec![StatisticsIssue::InvalidMinMax { row_groups: 1 }]
+        );
+    }
+
+    #[test]
+    fn test_read_levels() {
+        let item = Arc::new(Field::new_list_field(DataType::Utf8, true));
+        let mut tags = ListBuilder::new(StringBuilder::new()).with_field(item.clone());
+        tags.append_value([Some("a"), Some("b")]);
+        tags.append_null();
+        tags.append_value(Vec::<Option<&str>>::new());
+        tags.append_value([None, Some("c")]);
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("tags", DataType::List(item), true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3, 4])),
+                Arc::new(tags.finish()),
+            ],
+        )
+        .unwrap();
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut writer =
+            ArrowWriter::try_new(temp_file.reopen().unwrap(), schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let file = ParquetViewerFile::open(temp_file.path()).unwrap();
+        let leaves = file.leaf_columns().unwrap();
+        assert_eq!(leaves[0].path, "id");
+        assert_eq!((leaves[0].max_def_level, leaves[0].max_rep_level), (0, 0));
+        assert_eq!(leaves[1].path, "tags.list.item");
+        assert_eq!((leaves[1].max_def_level, leaves[1].max_rep_level), (3, 1));
+
+        let levels = file.read_levels("tags.list.item", None, 10).unwrap();
+        let levels: Vec<_> = levels
+            .iter()
+            .map(|l| (l.row_group, l.rep_level, l.def_level, l.value.as_deref()))
+            .collect();
+        assert_eq!(
+            levels,
+            vec![
+                (0, 0, 3, Some("a")),
+                (0, 1, 3, Some("b")),
+                // Null list
+                (0, 0, 0, None),
+                // Empty list
+                (1, 0, 1, None),
+                // Null item
+                (1, 0, 2, None),
+                (1, 1, 3, Some("c")),
+            ]
+        );
+
+        // Required columns without lists have no stored levels
+        let levels = file.read_levels("id", Some(1), 1).unwrap();
+        assert_eq!(
+            levels,
+            vec![LevelValue {
+                row_group: 1,
+                rep_level: 0,
+                def_level: 0,
+                value: Some("3".to_string()),
+            }]
+        );
+
+        match file.read_levels("tags.list.iten", None, 10).unwrap_err() {
+            ParquetViewerError::UnknownColumn { suggestions, .. } => {
+                assert_eq!(suggestions, vec!["tags.list.item".to_string()])
+            }
+            error => panic!("unexpected error: {error}"),
+        }
+        let error = file.read_levels("id", Some(2), 10).unwrap_err();
+        assert!(matches!(
+            error,
+            ParquetViewerError::RowGroupOutOfRange { index: 2, .. }
+        ));
+    }
+
+    #[test]
+    fn test_read_pages() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from((0..10).collect::<Vec<_>>())),
+                Arc::new(StringArray::from(vec![
+                    Some("a"),
+                    None,
+                    Some("b"),
+                    Some("a"),
+                    None,
+                    Some("c"),
+                    Some("b"),
+                    Some("a"),
+                    None,
+                    Some("c"),
+                ])),
+            ],
+        )
+        .unwrap();
+        let props = WriterProperties::builder()
+            .set_writer_version(WriterVersion::PARQUET_2_0)
+            .set_statistics_enabled(EnabledStatistics::Page)
+            .set_write_page_header_statistics(true)
+            .set_data_page_row_count_limit(4)
+            .set_write_batch_size(4)
+            .build();
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut writer =
+            ArrowWriter::try_new(temp_file.reopen().unwrap(), schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let file = ParquetViewerFile::open(temp_file.path()).unwrap();
+        let pages = file.read_pages("name", None).unwrap();
+        assert_eq!(pages.len(), 4);
+
+        let dictionary = &pages[0];
+        assert_eq!(dictionary.page_type, "DICTIONARY_PAGE");
+        assert_eq!(dictionary.encoding, "PLAIN");
+        assert_eq!(dictionary.num_values, 3);
+        assert!(dictionary.statistics.is_none());
+
+        let first = &pages[1];
+        assert_eq!(first.page_type, "DATA_PAGE_V2");
+        assert_eq!(first.encoding, "RLE_DICTIONARY");
+        assert_eq!(first.num_values, 4);
+        assert_eq!(first.num_rows, Some(4));
+        assert_eq!(first.num_nulls, Some(1));
+        assert_eq!(first.crc, None);
+        let statistics = first.statistics.as_ref().unwrap();
+        assert_eq!(statistics.min.as_deref(), Some("a"));
+        assert_eq!(statistics.max.as_deref(), Some("b"));
+        assert_eq!(pages[3].num_rows, Some(2));
+
+        // Headers and pages make up the whole column chunk
+        let row_groups = file.detailed_metadata().unwrap().row_groups.unwrap();
+        let chunk = &row_groups[0].columns[1];
+        let page_size: usize = pages
+            .iter()
+            .map(|p| p.header_size + p.compressed_size)
+            .sum();
+        assert_eq!(page_size as i64, chunk.compressed_size);
+        assert_eq!(Some(pages[0].offset as i64), chunk.dictionary_page_offset);
+        assert_eq!(pages[1].offset as i64, chunk.data_page_offset);
+
+        let error = file.read_pages("name", Some(1)).unwrap_err();
+        assert!(matches!(
+            error,
+            ParquetViewerError::RowGroupOutOfRange { index: 1, .. }
+        ));
+
+        // Corrupt footers pointing before the start or past the end of the file
+        let bytes = std::fs::read(temp_file.path()).unwrap();
+        for (offset, size) in [(-5, 10), (4, -1), (4, i64::MAX / 2), (i64::MAX, 1)] {
+            let mut corrupt = bytes.clone();
+            rewrite_column_chunks(&mut corrupt, |_, column| {
+                column
+                    .set_dictionary_page_offset(None)
+                    .set_data_page_offset(offset)
+                    .set_total_compressed_size(size)
+            });
+            let file = ParquetViewerFile::try_new(Bytes::from(corrupt)).unwrap();
+            let error = file.read_pages("name", None).unwrap_err();
+            assert!(
+                error.to_string().contains("lies outside the file"),
+                "{error}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_probe_bloom_filters() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("count", DataType::UInt32, false),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3, -4])),
+                Arc::new(UInt32Array::from(vec![1, 2, 3, u32::MAX])),
+                Arc::new(StringArray::from(vec!["a", "b", "c", "d"])),
+            ],
+        )
+        .unwrap();
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .set_column_bloom_filter_enabled("id".into(), true)
+            .set_column_bloom_filter_enabled("count".into(), true)
+            .build();
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut writer =
+            ArrowWriter::try_new(temp_file.reopen().unwrap(), schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let checks = |column: &str, value: &str| {
+            probe_bloom_filters(temp_file.path(), column, value)
+                .unwrap()
+                .iter()
+                .map(|probe| probe.check)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            checks("id", "-4"),
+            vec![BloomFilterCheck::Absent, BloomFilterCheck::MaybePresent]
+        );
+        assert_eq!(
+            checks("count", &u32::MAX.to_string()),
+            vec![BloomFilterCheck::Absent, BloomFilterCheck::MaybePresent]
+        );
+        assert_eq!(
+            checks("name", "a"),
+            vec![BloomFilterCheck::NoBloomFilter; 2]
+        );
+
+        let row_groups = read_detailed_metadata(temp_file.path())
+            .unwrap()
+            .row_groups
+            .unwrap();
+        let id = &row_groups[0].columns[0];
+        assert!(id.bloom_filter_offset.is_some());
+        assert!(id.bloom_filter_length.is_some());
+        assert_eq!(row_groups[0].columns[2].bloom_filter_offset, None);
+
+        let error = probe_bloom_filters(temp_file.path(), "id", "a").unwrap_err();
+        assert!(matches!(error, ParquetViewerError::InvalidValue(_)));
+    }
+
+    #[test]
+    fn test_validate_file() {
+        let temp_file = create_multi_row_group_parquet_file();
+        let report = validate_file(temp_file.path()).unwrap();
+        assert!(report.is_valid(), "{:?}", report.issues);
+        assert_eq!(report.num_row_groups, 10);
+        assert_eq!(report.column_chunks_checked, 10);
+        assert!(report.pages_checked >= 10);
+
+        let bytes = std::fs::read(temp_file.path()).unwrap();
+        let metadata = read_detailed_metadata(temp_file.path()).unwrap();
+        let row_groups = metadata.row_groups.unwrap();
+
+        // Truncated files lose their footer
+        let truncated = Bytes::copy_from_slice(&bytes[..bytes.len() - 100]);
+        let report = validate_from_reader(truncated).unwrap();
+        assert!(matches!(
+            report.issues[..],
+            [ValidationIssue::BadFooterMagic { .. }]
+        ));
+
+        let mut corrupt = bytes.clone();
+        let footer_length = corrupt.len() as u32;
+        let end = corrupt.len() - 8;
+        corrupt[end..end + 4].copy_from_slice(&footer_length.to_le_bytes());
+        let report = validate_from_reader(Bytes::from(corrupt)).unwrap();
+        assert!(matches!(
+            report.issues[..],
+            [ValidationIssue::BadFooterLength { .. }]
+        ));
+
+        // Corrupt the header of the first page of the third row group
+        let mut corrupt = bytes.clone();
+        let offset = row_groups[2].columns[0]
+            .dictionary_page_offset
+            .unwrap_or(row_groups[2].columns[0].data_page_offset) as usize;
+        corrupt[offset + 1..offset + 3].copy_from_slice(&[0x7f, 0x7f]);
+        corrupt[..4].copy_from_slice(b"PAR0");
+        let report = validate_from_reader(Bytes::from(corrupt)).unwrap();
+        assert!(!report.is_valid());
+        assert_eq!(report.column_chunks_checked, 9);
+        assert_eq!(report.issues.len(), 2);
+        assert!(matches!(
+            report.issues[0],
+            ValidationIssue::BadHeaderMagic { .. }
+        ));
+        match &report.issues[1] {
+            ValidationIssue::CorruptPageHeader {
+                row_group,
+                column,
+                offset: page_offset,
+                ..
+            } => {
+                assert_eq!(*row_group, 2);
+                assert_eq!(column, "id");
+                assert_eq!(*page_offset, offset as u64);
+            }
+            issue => panic!("unexpected issue: {issue}"),
+        }
+
+        let error = validate_file(create_test_arrow_file().path()).unwrap_err();
+        assert!(matches!(error, ParquetViewerError::ParquetRequired(_)));
+    }
+
+    #[test]
+    fn test_validate_chunk_out_of_bounds() {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+        )
+        .unwrap();
+        let mut bytes = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut bytes, schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        // Negative, before the leading magic bytes, and past the footer
+        for (offset, length) in [(-5, 10), (4, -1), (0, 10), (4, 1 << 40)] {
+            let mut corrupt = bytes.clone();
+            rewrite_column_chunks(&mut corrupt, |_, column| {
+                column
+                    .set_dictionary_page_offset(None)
+                    .set_data_page_offset(offset)
+                    .set_total_compressed_size(length)
+            });
+            let report = validate_from_reader(Bytes::from(corrupt)).unwrap();
+            assert_eq!(
+                report.issues,
+                vec![ValidationIssue::ChunkOutOfBounds {
+                    row_group: 0,
+                    column: "id".to_string(),
+                    offset,
+                    length,
+                }]
+            );
+            assert_eq!(report.column_chunks_checked, 0);
+        }
+    }
+
+    #[test]
+    fn test_audit_statistics() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let names = ["abc", "zzz", "\u{fc}ber"];
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from((0..100).collect::<Vec<i32>>())),
+                Arc::new(StringArray::from_iter(
+                    (0..100).map(|i| names.get(i % 4).copied()),
+                )),
+            ],
+        )
+        .unwrap();
+        let mut bytes = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut bytes, schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let audits = audit_statistics_from_reader(Bytes::from(bytes.clone())).unwrap();
+        assert_eq!(audits.len(), 2);
+        assert!(audits.iter().all(ChunkStatisticsAudit::is_consistent));
+        assert_eq!(audits[1].actual_min.as_deref(), Some("abc"));
+        assert_eq!(audits[1].actual_max.as_deref(), Some("\u{fc}ber"));
+        assert_eq!(audits[1].actual_null_count, 25);
+
+        // Rewrite the footer with a wrong min, and with string statistics in
+        // the deprecated fields, ordered as signed bytes by an old writer
+        use parquet::data_type::ByteArray;
+        use parquet::file::metadata::{ParquetMetaDataReader, ParquetMetaDataWriter};
+        use parquet::file::statistics::Statistics;
+        let metadata = ParquetMetaDataReader::new()
+            .parse_and_finish(&Bytes::from(bytes.clone()))
+            .unwrap();
+        let row_group = metadata.row_group(0).clone();
+        let columns = vec![
+            row_group
+                .column(0)
+                .clone()
+                .into_builder()
+                .set_statistics(Statistics::new(Some(5), Some(99), None, Some(0), false))
+                .build()
+                .unwrap(),
+            row_group
+                .column(1)
+                .clone()
+                .into_builder()
+                .set_statistics(Statistics::new(
+                    Some(ByteArray::from("\u{fc}ber")),
+                    Some(ByteArray::from("zzz")),
+                    None,
+                    Some(0),
+                    true,
+                ))
+                .build()
+                .unwrap(),
+        ];
+        let row_group = row_group
+            .into_builder()
+            .set_column_metadata(columns)
+            .build()
+            .unwrap();
+        let metadata = metadata
+            .into_builder()
+            .set_row_groups(vec![row_group])
+            .build();
+
+        let footer_length = u32::from_le_bytes(bytes[bytes.len() - 8..][..4].try_into().unwrap());
+        bytes.truncate(bytes.len() - 8 - footer_length as usize);
+        ParquetMetaDataWriter::new(&mut bytes, &metadata)
+            .finish()
+            .unwrap();
+
+        let audits = audit_statistics_from_reader(Bytes::from(bytes)).unwrap();
+        assert_eq!(
+            audits[0].mismatches,
+            vec![StatisticsMismatch::Min {
+                stored: "5".to_string(),
+                actual: Some("0".to_string()),
+                exact: true,
+            }]
+        );
+        assert!(audits[1].deprecated_min_max);
+        assert_eq!(
+            audits[1].mismatches,
+            vec![
+                StatisticsMismatch::Min {
+                    stored: "\u{fc}ber".to_string(),
+                    actual: Some("abc".to_string()),
+                    exact: true,
+                },
+                StatisticsMismatch::Max {
+                    stored: "zzz".to_string(),
+                    actual: Some("\u{fc}ber".to_string()),
+                    exact: true,
+                },
+                StatisticsMismatch::NullCount {
+                    stored: 0,
+                    actual: 25
+                },
+            ]
+        );
+
+        let error = audit_statistics(create_test_arrow_file().path()).unwrap_err();
+        assert!(matches!(error, ParquetViewerError::ParquetRequired(_)));
+    }
+
+    #[test]
+    fn test_audit_statistics_empty_and_null_chunks() {
+        use parquet::data_type::{ByteArray, ByteArrayType};
+        use parquet::file::statistics::Statistics;
+        use parquet::file::writer::SerializedFileWriter;
+        use parquet::schema::parser::parse_message_type;
+
+        // A chunk without values, then one of only nulls
+        let schema = parse_message_type("message test { optional binary name (STRING); }").unwrap();
+        let mut bytes = Vec::new();
+        let mut writer =
+            SerializedFileWriter::new(&mut bytes, Arc::new(schema), Default::default()).unwrap();
+        for def_levels in [vec![], vec![0, 0, 0]] {
+            let mut row_group = writer.next_row_group().unwrap();
+            let mut column = row_group.next_column().unwrap().unwrap();
+            column
+                .typed::<ByteArrayType>()
+                .write_batch(&[], Some(&def_levels), None)
+                .unwrap();
+            column.close().unwrap();
+            row_group.close().unwrap();
+        }
+        writer.close().unwrap();
+
+        let audits = audit_statistics_from_reader(Bytes::from(bytes.clone())).unwrap();
+        assert_eq!(audits.len(), 2);
+        assert!(audits.iter().all(ChunkStatisticsAudit::is_consistent));
+        assert!(audits.iter().all(|audit| audit.actual_min.is_none()));
+        assert_eq!(audits[0].actual_null_count, 0);
+        assert_eq!(audits[1].actual_null_count, 3);
+
+        // Min and max stored for chunks without any value
+        rewrite_column_chunks(&mut bytes, |_, column| {
+            column.set_statistics(Statistics::new(
+                Some(ByteArray::from("a")),
+                Some(ByteArray::from("b")),
+                None,
+                Some(0),
+                false,
+            ))
+        });
+        let audits = audit_statistics_from_reader(Bytes::from(bytes)).unwrap();
+        for audit in &audits {
+            assert_eq!(
+                audit.mismatches[..2],
+                [
+                    StatisticsMismatch::Min {
+                        stored: "a".to_string(),
+                        actual: None,
+                        exact: true,
+                    },
+                    StatisticsMismatch::Max {
+                        stored: "b".to_string(),
+                        actual: None,
+                        exact: true,
+                    },
+                ]
+            );
+        }
+        assert_eq!(audits[0].mismatches.len(), 2);
+        assert_eq!(
+            audits[1].mismatches[2],
+            StatisticsMismatch::NullCount {
+                stored: 0,
+                actual: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_audit_statistics_short_float16() {
+        use parquet::data_type::FixedLenByteArray;
+        use parquet::file::statistics::Statistics;
+
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "value",
+            DataType::Float16,
+            false,
+        )]));
+        let values = Float32Array::from(vec![1.0, 2.0]);
+        let values = arrow::compute::cast(&values, &DataType::Float16).unwrap();
+        let batch = RecordBatch::try_new(schema.clone(), vec![values]).unwrap();
+        let mut bytes = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut bytes, schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        // Statistics of a single byte cannot be decoded as a Float16
+        rewrite_column_chunks(&mut bytes, |_, column| {
+            column.set_statistics(Statistics::new(
+                Some(FixedLenByteArray::from(vec![0x3c])),
+                Some(FixedLenByteArray::from(vec![0x40])),
+                None,
+                Some(0),
+                false,
+            ))
+        });
+        let audits = audit_statistics_from_reader(Bytes::from(bytes)).unwrap();
+        assert_eq!(audits[0].actual_min.as_deref(), Some("1"));
+        assert_eq!(audits[0].stored_min, None);
+        assert_eq!(
+            audits[0].mismatches,
+            vec![
+                StatisticsMismatch::InvalidMin { bytes: vec![0x3c] },
+                StatisticsMismatch::InvalidMax { bytes: vec![0x40] },
+            ]
+        );
+        assert_eq!(
+            audits[0].mismatches[0].to_string(),
+            "min 3c cannot be decoded"
+        );
+    }
+
+    #[test]
+    fn test_audit_statistics_truncated_buffer() {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from_iter_values(0..10_000))],
+        )
+        .unwrap();
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(1000)
+            .build();
+        let mut bytes = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut bytes, schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        // Chunks past the end are findings, the others are still audited
+        let audits = audit_statistics_from_reader(truncate_keeping_footer(&bytes)).unwrap();
+        assert_eq!(audits.len(), 10);
+        assert!(audits[0].is_consistent());
+        assert_eq!(audits[1].actual_min.as_deref(), Some("1000"));
+        let last = &audits[9];
+        assert!(!last.is_consistent());
+        assert_eq!(last.stored_min.as_deref(), Some("9000"));
+        assert_eq!(last.actual_min, None);
+        assert_eq!(
+            last.mismatches,
+            vec![StatisticsMismatch::Unreadable {
+                error: "the column chunk lies outside the file".to_string()
+            }]
+        );
+        assert_eq!(
+            last.mismatches[0].to_string(),
+            "cannot read the column chunk: the column chunk lies outside the file"
+        );
+    }
+
+    #[test]
+    fn test_recover_file() {
+        let temp_file = create_multi_row_group_parquet_file();
+        let output = NamedTempFile::new().unwrap();
+        let report = recover_file(temp_file.path(), output.path(), None).unwrap();
+        assert!(report.footer_intact);
+        assert_eq!(report.recovered_row_groups, 10);
+        assert_eq!(report.recovered_rows, 100);
+        assert!(report.lost_row_groups.is_empty());
+
+        // A writer that crashed in the middle of the eighth row group
+        let bytes = std::fs::read(temp_file.path()).unwrap();
+        let metadata = read_detailed_metadata(temp_file.path()).unwrap();
+        let column = &metadata.row_groups.unwrap()[7].columns[0];
+        let start = column
+            .dictionary_page_offset
+            .unwrap_or(column.data_page_offset);
+        let end = (start + column.compressed_size / 2) as usize;
+        let truncated = Bytes::copy_from_slice(&bytes[..end]);
+
+        let error = recover_from_reader(truncated.clone(), Vec::new(), None).unwrap_err();
+        assert!(matches!(error, ParquetViewerError::Unrecoverable(_)));
+        assert!(error.to_string().contains("schema hint file is needed"));
+
+        let mut output = Vec::new();
+        let report =
+            recover_from_reader(truncated.clone(), &mut output, Some(temp_file.path())).unwrap();
+        assert!(!report.footer_intact);
+        assert_eq!(report.recovered_row_groups, 7);
+        assert_eq!(report.recovered_rows, 70);
+        assert_eq!(report.lost_row_groups.len(), 1);
+        assert_eq!(report.lost_row_groups[0].row_group, 7);
+        assert_eq!(report.lost_row_groups[0].num_rows, None);
+
+        let batches = read_batches_from_reader(Bytes::from(output), &ReadOptions::default())
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(collect_ids(&batches), (0..70).collect::<Vec<i32>>());
+
+        // Codecs are detected when the hint is an Arrow file
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from((0..100).collect::<Vec<i32>>()))],
+        )
+        .unwrap();
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(25)
+            .set_compression(parquet::basic::Compression::ZSTD(Default::default()))
+            .build();
+        let mut bytes = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut bytes, schema.clone(), Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let hint = NamedTempFile::new().unwrap();
+        let mut writer = ArrowFileWriter::try_new(hint.reopen().unwrap(), &schema).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+
+        let truncated = Bytes::copy_from_slice(&bytes[..bytes.len() - 50]);
+        let mut output = Vec::new();
+        let report = recover_from_reader(truncated, &mut output, Some(hint.path())).unwrap();
+        assert_eq!(report.recovered_row_groups, 4);
+        assert!(report.lost_row_groups.is_empty());
+        assert!(report.trailing_bytes > 0);
+
+        let metadata = read_detailed_metadata_from_reader(Bytes::from(output)).unwrap();
+        assert_eq!(metadata.total_records, 100);
+        assert_eq!(
+            metadata.row_groups.unwrap()[0].columns[0].codec,
+            "ZSTD(ZstdLevel(1))"
+        );
+    }
+
+    #[test]
+    fn test_recover_file_keeps_existing_files() {
+        let temp_file = create_multi_row_group_parquet_file();
+        let bytes = std::fs::read(temp_file.path()).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+
+        // The output would truncate the input or the schema hint
+        let error = recover_file(temp_file.path(), temp_file.path(), None).unwrap_err();
+        assert!(matches!(error, ParquetViewerError::Unrecoverable(_)));
+        let truncated = dir.path().join("truncated.parquet");
+        std::fs::write(&truncated, &bytes[..bytes.len() - 50]).unwrap();
+        let error = recover_file(&truncated, temp_file.path(), Some(temp_file.path())).unwrap_err();
+        assert!(matches!(error, ParquetViewerError::Unrecoverable(_)));
+        assert_eq!(std::fs::read(temp_file.path()).unwrap(), bytes);
+
+        // A failed recovery leaves an existing output alone
+        let output = dir.path().join("output.parquet");
+        std::fs::write(&output, b"previous").unwrap();
+        let error = recover_file(&truncated, &output, None).unwrap_err();
+        assert!(matches!(error, ParquetViewerError::Unrecoverable(_)));
+        assert_eq!(std::fs::read(&output).unwrap(), b"previous");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+
+        let report = recover_file(&truncated, &output, Some(temp_file.path())).unwrap();
+        assert_eq!(report.recovered_rows, 100);
+        assert_eq!(read_metadata(&output).unwrap().total_records, 100);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_recover_keeps_codecs_and_row_groups() {
+        use parquet::basic::{Compression, ZstdLevel};
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+        let num_rows = DEFAULT_MAX_ROW_GROUP_SIZE + 10;
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from_iter_values(0..num_rows as i32)),
+                Arc::new(StringArray::from_iter_values(
+                    (0..num_rows).map(|i| if i % 2 == 0 { "a" } else { "b" }),
+                )),
+            ],
+        )
+        .unwrap();
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(num_rows)
+            .set_column_compression("id".into(), Compression::ZSTD(ZstdLevel::default()))
+            .set_column_compression("name".into(), Compression::SNAPPY)
+            .build();
+        let mut bytes = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut bytes, schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let mut output = Vec::new();
+        let report = recover_from_reader(Bytes::from(bytes), &mut output, None).unwrap();
+        assert_eq!(report.recovered_rows, num_rows as u64);
+
+        let metadata = read_detailed_metadata_from_reader(Bytes::from(output)).unwrap();
+        let row_groups = metadata.row_groups.unwrap();
+        assert_eq!(row_groups.len(), 1);
+        assert_eq!(row_groups[0].num_rows, num_rows as i64);
+        let codecs: Vec<_> = row_groups[0]
+            .columns
+            .iter()
+            .map(|c| c.codec.as_str())
+            .collect();
+        assert_eq!(codecs, vec!["ZSTD(ZstdLevel(1))", "SNAPPY"]);
+    }
+
+    #[test]
+    fn test_recover_corrupt_footer() {
+        let temp_file = create_multi_row_group_parquet_file();
+        let mut bytes = std::fs::read(temp_file.path()).unwrap();
+        rewrite_column_chunks(&mut bytes, |row_group, column| match row_group {
+            2 => column
+                .set_dictionary_page_offset(None)
+                .set_data_page_offset(-5),
+            5 => column.set_total_compressed_size(1 << 40),
+            _ => column,
+        });
+
+        let mut output = Vec::new();
+        let report = recover_from_reader(Bytes::from(bytes), &mut output, None).unwrap();
+        assert!(report.footer_intact);
+        assert_eq!(report.recovered_row_groups, 8);
+        let lost: Vec<_> = report
+            .lost_row_groups
+            .iter()
+            .map(|lost| (lost.row_group, lost.reason.as_str()))
+            .collect();
+        assert_eq!(
+            lost,
+            vec![
+                (2, "column id lies outside the file"),
+                (5, "column id lies outside the file")
+            ]
+        );
+        assert_eq!(report.lost_row_groups[0].offset, 0);
+
+        let batches = read_batches_from_reader(Bytes::from(output), &ReadOptions::default())
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            collect_ids(&batches),
+            (0..20).chain(30..50).chain(60..100).collect::<Vec<i32>>()
+        );
+    }
+
+    #[test]
+    fn test_read_data_with_row_groups() {
+        let temp_file = create_multi_row_group_parquet_file();
+        let options = ReadOptions {
+            row_groups: Some(vec![7..=7, 2..=2]),
+            offset: 5,
+            limit: Some(10),
+            ..Default::default()
+        };
+        let batches = read_data_with_options(temp_file.path(), &options).unwrap();
+        assert_eq!(
+            collect_ids(&batches),
+            (25..30).chain(70..75).collect::<Vec<_>>()
+        );
+
+        let options = ReadOptions {
+            row_groups: Some(vec![3..=3, 10..=10]),
+            ..Default::default()
+        };
+        assert!(matches!(
+            read_batches_with_options(temp_file.path(), &options),
+            Err(ParquetViewerError::RowGroupOutOfRange {
+                index: 10,
+                num_row_groups: 10
+            })
+        ));
+
+        let options = ReadOptions {
+            row_groups: Some(vec![4..=4_000_000_000]),
+            ..Default::default()
+        };
+        assert!(matches!(
+            read_batches_with_options(temp_file.path(), &options),
+            Err(ParquetViewerError::RowGroupOutOfRange {
+                index: 10,
+                num_row_groups: 10
+            })
+        ));
+
+        let temp_file = create_test_arrow_stream_file();
+        let options = ReadOptions {
+            row_groups: Some(vec![1..=1]),
+            ..Default::default()
+        };
+        let batches = read_data_with_options(temp_file.path(), &options).unwrap();
+        assert_eq!(collect_ids(&batches), vec![4, 5]);
+
+        let options = ReadOptions {
+            row_groups: Some(vec![1..=usize::MAX]),
+            ..Default::default()
+        };
+        let reader = read_ipc_stream(File::open(temp_file.path()).unwrap(), &options).unwrap();
+        let batches = reader.collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(collect_ids(&batches), vec![4, 5]);
+    }
+
+    #[test]
+    fn test_read_truncated_buffer() {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from_iter_values(0..10_000))],
+        )
+        .unwrap();
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(1000)
+            .build();
+        let mut bytes = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut bytes, schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let bytes = truncate_keeping_footer(&bytes);
+
+        let file = ParquetViewerFile::try_new(bytes).unwrap();
+        let read = |options: &ReadOptions| {
+            file.read_batches(options)
+                .and_then(|reader| reader.collect::<Result<Vec<_>>>())
+        };
+        let batches = read(&ReadOptions {
+            row_groups: Some(vec![1..=1]),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(collect_ids(&batches), (1000..2000).collect::<Vec<_>>());
+
+        // Row groups past the end of the data fail instead of panicking
+        let error = read(&ReadOptions {
+            row_groups: Some(vec![8..=8]),
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert!(error.to_string().contains("past the end"), "{error}");
+        assert!(
+            read(&ReadOptions {
+                offset: 9500,
+                ..Default::default()
+            })
+            .is_err()
+        );
+        let error = file.read_levels("id", Some(8), 100).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Column chunk id of row group 8 lies outside the file"),
+            "{error}"
+        );
+        assert!(file.read_pages("id", Some(8)).is_err());
+        assert!(file.top_values("id", 3, &ReadOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_parquet_viewer_file_reuses_footer() {
+        let temp_file = create_multi_row_group_parquet_file();
+        let file = ParquetViewerFile::open(temp_file.path()).unwrap();
+
+        assert!(matches!(file.format(), FileFormat::Parquet));
+        assert_eq!(file.schema().field(0).name(), "id");
+        assert_eq!(file.metadata().unwrap().total_row_groups, 10);
+
+        let options = ReadOptions {
+            offset: 10,
+            limit: Some(5),
+            ..Default::default()
+        };
+        let first = file.read_data(&options).unwrap();
+        let second = file.read_data(&options).unwrap();
+        assert_eq!(collect_ids(&first), (10..15).collect::<Vec<_>>());
+        assert_eq!(collect_ids(&first), collect_ids(&second));
+
+        let batches: Vec<_> = file
+            .read_batches_with_projection(vec![0], None, Some(3))
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(collect_ids(&batches), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_file_not_found() {
+        let result = read_schema(Path::new("/nonexistent/file.parquet"));
+        assert!(matches!(result, Err(ParquetViewerError::FileNotFound(_))));
+    }
+
+    /// Bytes of the test Arrow file, with the lengths of the first record
+    /// batch block of its footer replaced.
+    fn corrupt_arrow_block(metadata_length: Option<i32>, body_length: Option<i64>) -> Bytes {
+        let temp_file = create_test_arrow_file();
+        let mut bytes = std::fs::read(temp_file.path()).unwrap();
+        let end = bytes.len() - 10;
+        let footer_length = i32::from_le_bytes(bytes[end..end + 4].try_into().unwrap()) as usize;
+        let start = end - footer_length;
+        let footer = root_as_footer(&bytes[start..end]).unwrap();
+        let block = footer.recordBatches().unwrap().get(0);
+        let mut pattern = block.offset().to_le_bytes().to_vec();
+        pattern.extend(block.metaDataLength().to_le_bytes());
+        let position = start
+            + bytes[start..end]
+                .windows(pattern.len())
+                .position(|window| window == pattern)
+                .unwrap();
+
+        if let Some(length) = metadata_length {
+            bytes[position + 8..position + 12].copy_from_slice(&length.to_le_bytes());
+        }
+        if let Some(length) = body_length {
+            bytes[position + 16..position + 24].copy_from_slice(&length.to_le_bytes());
+        }
+        Bytes::from(bytes)
+    }
+
+    #[test]
+    fn test_read_metadata_corrupt_arrow_block() {
+        for length in [-1, i32::MAX] {
+            let error =
+                read_metadata_from_reader(corrupt_arrow_block(Some(length), None)).unwrap_err();
+            assert!(
+                matches!(error, ParquetViewerError::Arrow(ArrowError::ParseError(_))),
+                "{error}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_data_corrupt_arrow_block() {
+        for length in [-1, i64::MAX, i64::MAX - 10] {
+            let bytes = corrupt_arrow_block(None, Some(length));
+            let error = read_batches_from_reader(bytes, &ReadOptions::default())
+                .and_then(|reader| reader.collect::<Result<Vec<_>>>())
+                .unwrap_err();
+            assert!(
+                matches!(error, ParquetViewerError::Arrow(ArrowError::ParseError(_))),
+                "{error}"
+            );
+        }
+    }
+
+    fn create_test_arrow_file() -> NamedTempFile {
+        let temp_file = NamedTempFile::new().unwrap();
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+
+        let id_array = Int32Array::from(vec![1, 2, 3, 4, 5]);
+        let name_array = StringArray::from(vec!["Alice", "Bob", "Charlie", "David", "Eve"]);
+
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(id_array), Arc::new(name_array)],
+        )
+        .unwrap();
+
+        {
             let file = temp_file.reopen().unwrap();
             let mut writer = ArrowFileWriter::try_new(file, &schema).unwrap();
             writer.write(&batch).unwrap();
@@ -498,6 +3823,20 @@ mod tests {
         assert_eq!(metadata.created_by, Some("Arrow IPC".to_string()));
     }
 
+    #[test]
+    fn test_read_arrow_metadata_from_footer() {
+        let temp_file = create_test_arrow_file();
+        let metadata = read_metadata(temp_file.path()).unwrap();
+
+        let ipc = metadata.ipc.unwrap();
+        assert_eq!(ipc.metadata_version, "V5");
+        assert_eq!(ipc.compression, None);
+        assert_eq!(ipc.blocks.len(), 1);
+        assert_eq!(ipc.blocks[0].num_rows, 5);
+        assert!(ipc.blocks[0].offset > 0);
+        assert!(ipc.blocks[0].body_length > 0);
+    }
+
     #[test]
     fn test_read_arrow_data() {
         let temp_file = create_test_arrow_file();
@@ -509,6 +3848,52 @@ mod tests {
         assert_eq!(batches[0].num_columns(), 2);
     }
 
+    #[test]
+    fn test_read_arrow_data_honors_batch_size_and_limit() {
+        let temp_file = create_test_arrow_file();
+        let batches = read_data(temp_file.path(), Some(2), None).unwrap();
+        let rows: Vec<usize> = batches.iter().map(|b| b.num_rows()).collect();
+        assert_eq!(rows, vec![2, 2, 1]);
+
+        let batches = read_data(temp_file.path(), Some(2), Some(3)).unwrap();
+        assert_eq!(collect_ids(&batches), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_read_arrow_data_with_offset_window() {
+        let temp_file = create_test_arrow_file();
+        let options = ReadOptions {
+            offset: 1,
+            limit: Some(2),
+            ..Default::default()
+        };
+        let batches = read_data_with_options(temp_file.path(), &options).unwrap();
+
+        assert_eq!(collect_ids(&batches), vec![2, 3]);
+    }
+
+    #[test]
+    fn test_read_arrow_data_with_filter() {
+        let temp_file = create_test_arrow_file();
+        let options = ReadOptions {
+            column_indices: Some(vec![1]),
+            filter: Some(Filter::parse("name = 'Bob' OR id >= 4.5").unwrap()),
+            ..Default::default()
+        };
+        let batches = read_data_with_options(temp_file.path(), &options).unwrap();
+
+        assert_eq!(batches.len(), 1);
+        let names = batches[0]
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(
+            names.iter().flatten().collect::<Vec<_>>(),
+            vec!["Bob", "Eve"]
+        );
+    }
+
     #[test]
     fn test_read_arrow_data_with_projection() {
         let temp_file = create_test_arrow_file();
@@ -520,6 +3905,337 @@ mod tests {
         assert_eq!(batch.schema().field(0).name(), "name");
     }
 
+    fn create_nested_batch() -> RecordBatch {
+        let user_fields = Fields::from(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, false),
+        ]);
+        let user = StructArray::new(
+            user_fields.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![10, 20, 30])),
+                Arc::new(StringArray::from(vec!["a", "b", "c"])),
+            ],
+            None,
+        );
+        let payload = StructArray::new(
+            Fields::from(vec![
+                Field::new("user", DataType::Struct(user_fields), false),
+                Field::new("score", DataType::Int32, false),
+            ]),
+            vec![Arc::new(user), Arc::new(Int32Array::from(vec![7, 8, 9]))],
+            None,
+        );
+
+        RecordBatch::try_from_iter(vec![
+            ("id", Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef),
+            ("payload", Arc::new(payload) as ArrayRef),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_read_data_with_nested_columns() {
+        let batch = create_nested_batch();
+
+        let parquet_file = NamedTempFile::new().unwrap();
+        let mut writer =
+            ArrowWriter::try_new(parquet_file.reopen().unwrap(), batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let arrow_file = NamedTempFile::new().unwrap();
+        let mut writer =
+            ArrowFileWriter::try_new(arrow_file.reopen().unwrap(), &batch.schema()).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+
+        for temp_file in [parquet_file, arrow_file] {
+            let columns = vec!["payload.user.id".to_string(), "id".to_string()];
+            let batches = read_data_with_columns(temp_file.path(), columns, None, None).unwrap();
+
+            let schema = batches[0].schema();
+            assert_eq!(schema.fields().len(), 2);
+            assert_eq!(schema.field(0).name(), "id");
+            let DataType::Struct(payload) = schema.field(1).data_type() else {
+                panic!("expected a struct column");
+            };
+            assert_eq!(payload.len(), 1);
+            assert_eq!(
+                payload[0].data_type(),
+                &DataType::Struct(Fields::from(vec![Field::new("id", DataType::Int32, false)]))
+            );
+
+            let user_ids = batches[0]
+                .column(1)
+                .as_struct()
+                .column(0)
+                .as_struct()
+                .column(0)
+                .as_primitive::<arrow::datatypes::Int32Type>()
+                .values()
+                .to_vec();
+            assert_eq!(user_ids, vec![10, 20, 30]);
+        }
+    }
+
+    #[test]
+    fn test_invalid_projection_errors() {
+        for temp_file in [create_test_parquet_file(), create_test_arrow_file()] {
+            let result = read_data_with_projection(temp_file.path(), vec![0, 2], None, None);
+            assert!(matches!(
+                result,
+                Err(ParquetViewerError::ColumnIndexOutOfRange {
+                    index: 2,
+                    num_columns: 2
+                })
+            ));
+
+            let columns = vec!["nmae".to_string()];
+            let err = read_data_with_columns(temp_file.path(), columns, None, None).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Unknown column 'nmae', did you mean 'name'?"
+            );
+        }
+    }
+
+    #[test]
+    fn test_select_columns() {
+        let batch = create_nested_batch();
+        let schema = batch.schema();
+
+        let columns = select_columns(&schema, Some(&["re:^pay", "id"]), &[]).unwrap();
+        assert_eq!(columns, vec!["id", "payload"]);
+
+        let columns = select_columns(&schema, None, &["re:^i"]).unwrap();
+        assert_eq!(columns, vec!["payload"]);
+
+        let columns =
+            select_columns(&schema, Some(&["payload.user.id", "id"]), &["payload"]).unwrap();
+        assert_eq!(columns, vec!["id"]);
+
+        assert!(matches!(
+            select_columns(&schema, Some(&["re:("]), &[]),
+            Err(ParquetViewerError::InvalidColumnSelector(_))
+        ));
+
+        // Excluding fields of a struct column selected whole keeps the others
+        let columns =
+            select_columns(&schema, Some(&["payload", "id"]), &["payload.user.id"]).unwrap();
+        assert_eq!(columns, vec!["id", "payload.user.name", "payload.score"]);
+        let columns = select_columns(&schema, None, &["payload.user", "id"]).unwrap();
+        assert_eq!(columns, vec!["payload.score"]);
+
+        for (columns, exclude) in [
+            (None, vec!["id", "payload"]),
+            (
+                Some(vec!["payload.user"]),
+                vec!["payload.user.id", "payload.user.name"],
+            ),
+            (Some(vec!["re:^none"]), vec![]),
+        ] {
+            assert!(matches!(
+                select_columns(&schema, columns.as_deref(), &exclude),
+                Err(ParquetViewerError::InvalidColumnSelector(_))
+            ));
+        }
+
+        let temp_file = create_test_parquet_file();
+        let options = ReadOptions {
+            exclude_columns: Some(vec!["id".to_string()]),
+            ..Default::default()
+        };
+        let batches = read_data_with_options(temp_file.path(), &options).unwrap();
+        assert_eq!(batches[0].num_columns(), 1);
+        assert_eq!(batches[0].schema().field(0).name(), "name");
+
+        let options = ReadOptions {
+            exclude_columns: Some(vec!["id".to_string(), "name".to_string()]),
+            ..Default::default()
+        };
+        assert!(matches!(
+            read_data_with_options(temp_file.path(), &options),
+            Err(ParquetViewerError::InvalidColumnSelector(_))
+        ));
+    }
+
+    fn create_test_arrow_stream_file() -> NamedTempFile {
+        let temp_file = NamedTempFile::new().unwrap();
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+
+        let file = temp_file.reopen().unwrap();
+        let mut writer = ArrowStreamWriter::try_new(file, &schema).unwrap();
+        for (ids, names) in [
+            (vec![1, 2, 3], vec!["Alice", "Bob", "Charlie"]),
+            (vec![4, 5], vec!["David", "Eve"]),
+        ] {
+            let batch = RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int32Array::from(ids)),
+                    Arc::new(StringArray::from(names)),
+                ],
+            )
+            .unwrap();
+            writer.write(&batch).unwrap();
+        }
+        writer.finish().unwrap();
+
+        temp_file
+    }
+
+    #[test]
+    fn test_detect_arrow_stream_format() {
+        let temp_file = create_test_arrow_stream_file();
+        assert!(matches!(
+            detect_file_format(temp_file.path()).unwrap(),
+            FileFormat::ArrowStream
+        ));
+        assert!(matches!(
+            detect_file_format(Path::new("data.arrows")).unwrap(),
+            FileFormat::ArrowStream
+        ));
+    }
+
+    #[test]
+    fn test_read_arrow_stream_schema_and_metadata() {
+        let temp_file = create_test_arrow_stream_file();
+        let schema = read_schema(temp_file.path()).unwrap();
+        assert_eq!(schema.fields().len(), 2);
+        assert_eq!(schema.field(1).name(), "name");
+
+        let metadata = read_metadata(temp_file.path()).unwrap();
+        assert_eq!(metadata.total_records, 5);
+        assert_eq!(metadata.total_row_groups, 2);
+        assert_eq!(metadata.created_by, Some("Arrow IPC stream".to_string()));
+    }
+
+    #[test]
+    fn test_read_arrow_stream_data() {
+        let temp_file = create_test_arrow_stream_file();
+        let batches = read_data(temp_file.path(), None, None).unwrap();
+        assert_eq!(collect_ids(&batches), vec![1, 2, 3, 4, 5]);
+
+        let batches = read_data_with_projection(temp_file.path(), vec![1], None, Some(4)).unwrap();
+        let total_rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(total_rows, 4);
+        assert_eq!(batches[0].num_columns(), 1);
+        assert_eq!(batches[0].schema().field(0).name(), "name");
+    }
+
+    #[test]
+    fn test_read_arrow_stream_data_rechunks_across_batches() {
+        let temp_file = create_test_arrow_stream_file();
+        let batches = read_data(temp_file.path(), Some(4), None).unwrap();
+        let rows: Vec<usize> = batches.iter().map(|b| b.num_rows()).collect();
+        assert_eq!(rows, vec![4, 1]);
+        assert_eq!(collect_ids(&batches), vec![1, 2, 3, 4, 5]);
+
+        let batches = read_data(temp_file.path(), Some(4), Some(2)).unwrap();
+        assert_eq!(collect_ids(&batches), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_parquet_viewer_file_interleaved_stream_readers() {
+        let temp_file = create_test_arrow_stream_file();
+        let file = ParquetViewerFile::open(temp_file.path()).unwrap();
+
+        let mut first = file.read_batches(&ReadOptions::default()).unwrap();
+        let mut second = file.read_batches(&ReadOptions::default()).unwrap();
+        let a = first.next().unwrap().unwrap();
+        let b = second.next().unwrap().unwrap();
+        assert_eq!(collect_ids(&[a]), collect_ids(&[b]));
+        assert_eq!(file.metadata().unwrap().total_records, 5);
+        assert_eq!(first.next().unwrap().unwrap().num_rows(), 2);
+    }
+
+    #[test]
+    fn test_read_ipc_stream_from_reader() {
+        let temp_file = create_test_arrow_stream_file();
+        let options = ReadOptions {
+            offset: 2,
+            batch_size: Some(2),
+            ..Default::default()
+        };
+        let reader = read_ipc_stream(temp_file.reopen().unwrap(), &options).unwrap();
+        assert_eq!(reader.schema().fields().len(), 2);
+
+        let batches: Vec<_> = reader.collect::<Result<_>>().unwrap();
+        let rows: Vec<usize> = batches.iter().map(|b| b.num_rows()).collect();
+        assert_eq!(rows, vec![2, 1]);
+        assert_eq!(collect_ids(&batches), vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn test_format_from_magic() {
+        assert!(matches!(
+            format_from_magic(b"PAR1"),
+            Some(FileFormat::Parquet)
+        ));
+        assert!(matches!(
+            format_from_magic(b"ARROW1"),
+            Some(FileFormat::Arrow)
+        ));
+        assert!(matches!(
+            format_from_magic(&[0xFF, 0xFF, 0xFF, 0xFF]),
+            Some(FileFormat::ArrowStream)
+        ));
+        assert!(format_from_magic(b"GIF8").is_none());
+
+        let error = read_schema_from_reader(Bytes::from_static(b"GIF89a")).unwrap_err();
+        assert!(matches!(error, ParquetViewerError::UnknownFormat(_)));
+    }
+
+    #[test]
+    fn test_read_from_bytes() {
+        for temp_file in [
+            create_test_parquet_file(),
+            create_test_arrow_file(),
+            create_test_arrow_stream_file(),
+        ] {
+            let bytes = Bytes::from(std::fs::read(temp_file.path()).unwrap());
+
+            let schema = read_schema_from_reader(bytes.clone()).unwrap();
+            assert_eq!(schema.field(0).name(), "id");
+
+            let metadata = read_metadata_from_reader(bytes.clone()).unwrap();
+            assert_eq!(metadata.total_records, 5);
+            assert_eq!(metadata.file_size, bytes.len());
+
+            let options = ReadOptions {
+                offset: 1,
+                limit: Some(3),
+                ..Default::default()
+            };
+            let batches = read_data_from_reader(bytes, &options).unwrap();
+            assert_eq!(collect_ids(&batches), vec![2, 3, 4]);
+        }
+    }
+
+    #[test]
+    fn test_read_from_seek_reader() {
+        let temp_file = create_multi_row_group_parquet_file();
+        let data = std::fs::read(temp_file.path()).unwrap();
+        let reader = SeekReader::new(std::io::Cursor::new(data)).unwrap();
+
+        let file = ParquetViewerFile::try_new(reader).unwrap();
+        assert!(matches!(file.format(), FileFormat::Parquet));
+        assert_eq!(file.metadata().unwrap().total_records, 100);
+
+        let options = ReadOptions {
+            offset: 42,
+            limit: Some(2),
+            ..Default::default()
+        };
+        let batches = file.read_data(&options).unwrap();
+        assert_eq!(collect_ids(&batches), vec![42, 43]);
+    }
+
     #[test]
     fn test_sql_format_minimal() {
         let sql = "SELECT u.id, u.name, u.email, p.title as project_title, COUNT(t.id) as task_count FROM users u INNER JOIN projects p ON u.id = p.user_id LEFT JOIN tasks t ON p.id = t.project_id WHERE u.active = 1 AND p.status = 'active' AND t.completed = 0 GROUP BY u.id, p.id HAVING COUNT(t.id) > 0 ORDER BY u.name, task_count DESC LIMIT 10";
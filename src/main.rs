@@ -1,6 +1,20 @@
-use clap::{Arg, ArgAction, Command, command};
-use parquet_viewer::{SqlFormatStyle, read_data, read_metadata, read_schema, sql_format};
+use bytes::Bytes;
+use clap::{Arg, ArgAction, ArgMatches, Command, command};
+use parquet::file::reader::ChunkReader;
+use parquet_viewer::{
+    BloomFilterCheck, FileFormat, Filter, ParquetViewerFile, ReadOptions, RowGroupDetails,
+    SchemaNode, SqlFormatStyle, audit_statistics, audit_statistics_from_reader, format_from_magic,
+    probe_bloom_filters, probe_bloom_filters_from_reader, profile_batches,
+    read_batches_from_reader, read_batches_with_options, read_column_statistics,
+    read_column_statistics_from_reader, read_detailed_metadata, read_detailed_metadata_from_reader,
+    read_ipc_stream, read_metadata, read_metadata_from_reader, read_schema,
+    read_schema_from_reader, read_schema_tree, read_schema_tree_from_reader, recover_file,
+    recover_to_file, sql_format, top_values, top_values_from_reader, validate_file,
+    validate_from_reader,
+};
 use prettytable::{Cell, Row, Table};
+use std::io::Read;
+use std::ops::RangeInclusive;
 use std::path::Path;
 
 fn main() {
@@ -12,9 +26,16 @@ fn main() {
                 .about("Read and display the schema of a Parquet file")
                 .arg(
                     Arg::new("file")
-                        .help("Path to the Parquet file")
+                        .help("Path to the Parquet file (use '-' to read from stdin)")
                         .required(true)
                         .index(1),
+                )
+                .arg(
+                    Arg::new("tree")
+                        .short('t')
+                        .long("tree")
+                        .help("Show nested fields as a tree, with Parquet repetition, physical and logical types, field IDs and metadata")
+                        .action(ArgAction::SetTrue),
                 ),
         )
         .subcommand(
@@ -22,17 +43,51 @@ fn main() {
                 .about("Read and display metadata of a Parquet file")
                 .arg(
                     Arg::new("file")
-                        .help("Path to the Parquet file")
+                        .help("Path to the Parquet file (use '-' to read from stdin)")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("detailed")
+                        .short('d')
+                        .long("detailed")
+                        .help("Show the layout of every row group and column chunk")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("stats")
+                .about("Summarize Parquet column statistics across all row groups without reading data")
+                .arg(
+                    Arg::new("file")
+                        .help("Path to the Parquet file (use '-' to read from stdin)")
                         .required(true)
                         .index(1),
                 ),
         )
+        .subcommand(
+            Command::new("audit-stats")
+                .about("Decode every column chunk and check its stored min, max and null count, exiting with an error on mismatches")
+                .arg(
+                    Arg::new("file")
+                        .help("Path to the Parquet file (use '-' to read from stdin)")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("all")
+                        .short('a')
+                        .long("all")
+                        .help("Show every column chunk, not only those with mismatches")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
         .subcommand(
             Command::new("data")
                 .about("Read and display data from a Parquet file")
                 .arg(
                     Arg::new("file")
-                        .help("Path to the Parquet file")
+                        .help("Path to the Parquet file (use '-' to read from stdin)")
                         .required(true)
                         .index(1),
                 )
@@ -51,8 +106,194 @@ fn main() {
                         .help("Maximum number of rows to display")
                         .value_parser(clap::value_parser!(usize))
                         .action(ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("offset")
+                        .short('o')
+                        .long("offset")
+                        .help("Number of rows to skip before displaying")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("0")
+                        .action(ArgAction::Set),
+                )
+                .args(column_args())
+                .args(row_args()),
+        )
+        .subcommand(
+            Command::new("profile")
+                .about("Scan the data once and profile every column")
+                .arg(
+                    Arg::new("file")
+                        .help("Path to the Parquet file (use '-' to read from stdin)")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("limit")
+                        .short('l')
+                        .long("limit")
+                        .help("Only profile the first rows")
+                        .value_parser(clap::value_parser!(usize))
+                        .action(ArgAction::Set),
+                )
+                .args(column_args())
+                .args(row_args()),
+        )
+        .subcommand(
+            Command::new("top")
+                .about("Show the most frequent values of a column and their counts")
+                .arg(
+                    Arg::new("file")
+                        .help("Path to the Parquet file (use '-' to read from stdin)")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("column")
+                        .short('c')
+                        .long("column")
+                        .help("Column to count, nested fields as dotted paths")
+                        .required(true)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("top")
+                        .short('k')
+                        .long("top")
+                        .help("Number of values to show")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("20")
+                        .action(ArgAction::Set),
+                )
+                .args(row_args()),
+        )
+        .subcommand(
+            Command::new("validate")
+                .about("Check the footer, pages, checksums and row counts of a Parquet file, exiting with an error if it is corrupt")
+                .arg(
+                    Arg::new("file")
+                        .help("Path to the Parquet file (use '-' to read from stdin)")
+                        .required(true)
+                        .index(1),
+                ),
+        )
+        .subcommand(
+            Command::new("recover")
+                .about("Copy the intact row groups of a damaged Parquet file, such as one without a footer, to a new file")
+                .arg(
+                    Arg::new("file")
+                        .help("Path to the damaged Parquet file (use '-' to read from stdin)")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .help("Path of the recovered Parquet file, only written once recovery succeeds")
+                        .required(true)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("schema")
+                        .short('s')
+                        .long("schema")
+                        .help("Parquet or Arrow file with the same schema, needed when the footer is missing since the schema embedded in it is lost too")
+                        .action(ArgAction::Set),
+                ),
+        )
+        .subcommand(
+            Command::new("probe")
+                .about("Find the row groups that may contain a value using Parquet bloom filters")
+                .arg(
+                    Arg::new("file")
+                        .help("Path to the Parquet file (use '-' to read from stdin)")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("column")
+                        .short('c')
+                        .long("column")
+                        .help("Dotted path of the leaf column to probe")
+                        .required(true)
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("value")
+                        .short('v')
+                        .long("value")
+                        .help("Value to look for, fixed length binary values in hex")
+                        .required(true)
+                        .allow_hyphen_values(true)
+                        .action(ArgAction::Set),
                 ),
         )
+        .subcommand(
+            Command::new("inspect")
+                .about("Inspect the low-level structure of a Parquet file")
+                .subcommand(
+                    Command::new("levels")
+                        .about("Show the maximum repetition and definition levels of leaf columns, or the levels of the first values of one")
+                        .arg(
+                            Arg::new("file")
+                                .help("Path to the Parquet file (use '-' to read from stdin)")
+                                .required(true)
+                                .index(1),
+                        )
+                        .arg(
+                            Arg::new("column")
+                                .short('c')
+                                .long("column")
+                                .help("Dotted path of the leaf column to read, e.g. tags.list.item")
+                                .action(ArgAction::Set),
+                        )
+                        .arg(
+                            Arg::new("row-group")
+                                .short('g')
+                                .long("row-group")
+                                .help("Only read this row group")
+                                .value_parser(clap::value_parser!(usize))
+                                .action(ArgAction::Set),
+                        )
+                        .arg(
+                            Arg::new("limit")
+                                .short('l')
+                                .long("limit")
+                                .help("Number of values to show")
+                                .value_parser(clap::value_parser!(usize))
+                                .default_value("20")
+                                .action(ArgAction::Set),
+                        ),
+                )
+                .subcommand(
+                    Command::new("pages")
+                        .about("Show the page headers, encodings, sizes and statistics of a column chunk")
+                        .arg(
+                            Arg::new("file")
+                                .help("Path to the Parquet file (use '-' to read from stdin)")
+                                .required(true)
+                                .index(1),
+                        )
+                        .arg(
+                            Arg::new("column")
+                                .short('c')
+                                .long("column")
+                                .help("Dotted path of the leaf column, e.g. tags.list.item")
+                                .required(true)
+                                .action(ArgAction::Set),
+                        )
+                        .arg(
+                            Arg::new("row-group")
+                                .short('g')
+                                .long("row-group")
+                                .help("Only show the pages of this row group")
+                                .value_parser(clap::value_parser!(usize))
+                                .action(ArgAction::Set),
+                        ),
+                )
+                .subcommand_required(true),
+        )
         .subcommand(
             Command::new("sql")
                 .about("Format SQL queries")
@@ -78,18 +319,82 @@ fn main() {
     let result = match matches.subcommand() {
         Some(("schema", sub_matches)) => {
             let file_path = sub_matches.get_one::<String>("file").unwrap();
-            handle_schema(file_path)
+            let tree = sub_matches.get_flag("tree");
+            if tree {
+                handle_schema_tree(file_path)
+            } else {
+                handle_schema(file_path)
+            }
         }
         Some(("metadata", sub_matches)) => {
             let file_path = sub_matches.get_one::<String>("file").unwrap();
-            handle_metadata(file_path)
+            let detailed = sub_matches.get_flag("detailed");
+            handle_metadata(file_path, detailed)
+        }
+        Some(("stats", sub_matches)) => {
+            let file_path = sub_matches.get_one::<String>("file").unwrap();
+            handle_stats(file_path)
+        }
+        Some(("audit-stats", sub_matches)) => {
+            let file_path = sub_matches.get_one::<String>("file").unwrap();
+            handle_audit_stats(file_path, sub_matches.get_flag("all"))
         }
         Some(("data", sub_matches)) => {
             let file_path = sub_matches.get_one::<String>("file").unwrap();
-            let batch_size = sub_matches.get_one::<usize>("batch-size").copied();
-            let limit = sub_matches.get_one::<usize>("limit").copied();
-            handle_data(file_path, batch_size, limit)
+            let options = ReadOptions {
+                batch_size: sub_matches.get_one::<usize>("batch-size").copied(),
+                offset: *sub_matches.get_one::<usize>("offset").unwrap(),
+                limit: sub_matches.get_one::<usize>("limit").copied(),
+                ..selection_options(sub_matches)
+            };
+            handle_data(file_path, &options)
+        }
+        Some(("profile", sub_matches)) => {
+            let file_path = sub_matches.get_one::<String>("file").unwrap();
+            let options = ReadOptions {
+                limit: sub_matches.get_one::<usize>("limit").copied(),
+                ..selection_options(sub_matches)
+            };
+            handle_profile(file_path, &options)
+        }
+        Some(("top", sub_matches)) => {
+            let file_path = sub_matches.get_one::<String>("file").unwrap();
+            let column = sub_matches.get_one::<String>("column").unwrap();
+            let k = *sub_matches.get_one::<usize>("top").unwrap();
+            handle_top(file_path, column, k, &row_options(sub_matches))
+        }
+        Some(("validate", sub_matches)) => {
+            let file_path = sub_matches.get_one::<String>("file").unwrap();
+            handle_validate(file_path)
+        }
+        Some(("recover", sub_matches)) => {
+            let file_path = sub_matches.get_one::<String>("file").unwrap();
+            let output = sub_matches.get_one::<String>("output").unwrap();
+            let schema = sub_matches.get_one::<String>("schema");
+            handle_recover(file_path, output, schema.map(String::as_str))
+        }
+        Some(("probe", sub_matches)) => {
+            let file_path = sub_matches.get_one::<String>("file").unwrap();
+            let column = sub_matches.get_one::<String>("column").unwrap();
+            let value = sub_matches.get_one::<String>("value").unwrap();
+            handle_probe(file_path, column, value)
         }
+        Some(("inspect", sub_matches)) => match sub_matches.subcommand() {
+            Some(("levels", sub_matches)) => {
+                let file_path = sub_matches.get_one::<String>("file").unwrap();
+                let column = sub_matches.get_one::<String>("column");
+                let row_group = sub_matches.get_one::<usize>("row-group").copied();
+                let limit = *sub_matches.get_one::<usize>("limit").unwrap();
+                handle_inspect_levels(file_path, column.map(String::as_str), row_group, limit)
+            }
+            Some(("pages", sub_matches)) => {
+                let file_path = sub_matches.get_one::<String>("file").unwrap();
+                let column = sub_matches.get_one::<String>("column").unwrap();
+                let row_group = sub_matches.get_one::<usize>("row-group").copied();
+                handle_inspect_pages(file_path, column, row_group)
+            }
+            _ => unreachable!(),
+        },
         Some(("sql", sub_matches)) => {
             let query = sub_matches.get_one::<String>("query").unwrap();
             let style = sub_matches.get_one::<String>("style").unwrap();
@@ -104,9 +409,129 @@ fn main() {
     }
 }
 
+/// Arguments selecting the columns to read, shared by the `data` and
+/// `profile` subcommands.
+fn column_args() -> Vec<Arg> {
+    vec![
+        Arg::new("columns")
+            .short('c')
+            .long("columns")
+            .help("Comma-separated columns to read, nested fields as dotted paths, e.g. id,payload.user.id, or re:<pattern> to match column names")
+            .value_delimiter(',')
+            .action(ArgAction::Append),
+        Arg::new("exclude-columns")
+            .short('x')
+            .long("exclude-columns")
+            .help("Comma-separated columns to leave out, accepts the same selectors as --columns")
+            .value_delimiter(',')
+            .action(ArgAction::Append),
+    ]
+}
+
+/// Arguments selecting the rows to read, shared by the `data`, `profile` and
+/// `top` subcommands.
+fn row_args() -> Vec<Arg> {
+    vec![
+        Arg::new("row-groups")
+            .short('g')
+            .long("row-groups")
+            .help("Comma-separated row groups (record batches for Arrow files) to read, e.g. 3,7-9")
+            .value_delimiter(',')
+            .value_parser(parse_row_groups)
+            .action(ArgAction::Append),
+        Arg::new("where")
+            .short('w')
+            .long("where")
+            .help("Only read rows matching a filter, e.g. \"status = 412 AND host IS NOT NULL\"")
+            .value_parser(|expr: &str| Filter::parse(expr).map_err(|e| e.to_string()))
+            .action(ArgAction::Set),
+    ]
+}
+
+/// Read options from the arguments of [`column_args`] and [`row_args`].
+fn selection_options(matches: &ArgMatches) -> ReadOptions {
+    ReadOptions {
+        columns: matches
+            .get_many::<String>("columns")
+            .map(|columns| columns.cloned().collect()),
+        exclude_columns: matches
+            .get_many::<String>("exclude-columns")
+            .map(|columns| columns.cloned().collect()),
+        ..row_options(matches)
+    }
+}
+
+/// Read options from the arguments of [`row_args`].
+fn row_options(matches: &ArgMatches) -> ReadOptions {
+    ReadOptions {
+        row_groups: matches
+            .get_many::<RangeInclusive<usize>>("row-groups")
+            .map(|ranges| ranges.cloned().collect()),
+        filter: matches.get_one::<Filter>("where").cloned(),
+        ..Default::default()
+    }
+}
+
+/// Parse a row group index, or an inclusive range of them such as `7-9`.
+fn parse_row_groups(value: &str) -> Result<RangeInclusive<usize>, String> {
+    let parse = |index: &str| {
+        index
+            .trim()
+            .parse::<usize>()
+            .map_err(|e| format!("invalid row group '{index}': {e}"))
+    };
+
+    match value.split_once('-') {
+        Some((start, end)) => {
+            let (start, end) = (parse(start)?, parse(end)?);
+            if start > end {
+                return Err(format!("invalid row group range '{value}'"));
+            }
+            Ok(start..=end)
+        }
+        None => parse(value).map(|index| index..=index),
+    }
+}
+
+/// Input of the `schema`, `metadata` and `data` subcommands.
+enum Input {
+    File(String),
+    /// Arrow IPC stream read incrementally from stdin.
+    Stream(Box<dyn Read + Send>),
+    /// Parquet or Arrow IPC file spooled from stdin into memory, since both
+    /// need their footer.
+    Buffer(Bytes),
+}
+
+fn open_input(file_path: &str) -> parquet_viewer::Result<Input> {
+    if file_path != "-" {
+        return Ok(Input::File(file_path.to_string()));
+    }
+
+    let mut stdin = std::io::stdin();
+    let mut magic = [0u8; 4];
+    stdin.read_exact(&mut magic)?;
+
+    let reader = std::io::Cursor::new(magic).chain(stdin);
+    if matches!(format_from_magic(&magic), Some(FileFormat::ArrowStream)) {
+        return Ok(Input::Stream(Box::new(reader)));
+    }
+
+    Ok(Input::Buffer(read_to_bytes(reader)?))
+}
+
+fn read_to_bytes(mut reader: impl Read) -> parquet_viewer::Result<Bytes> {
+    let mut buffer = Vec::new();
+    reader.read_to_end(&mut buffer)?;
+    Ok(Bytes::from(buffer))
+}
+
 fn handle_schema(file_path: &str) -> parquet_viewer::Result<()> {
-    let path = Path::new(file_path);
-    let schema = read_schema(path)?;
+    let schema = match open_input(file_path)? {
+        Input::File(path) => read_schema(Path::new(&path))?,
+        Input::Stream(reader) => read_ipc_stream(reader, &ReadOptions::default())?.schema(),
+        Input::Buffer(bytes) => read_schema_from_reader(bytes)?,
+    };
 
     println!("Schema for: {}", file_path);
 
@@ -130,9 +555,86 @@ fn handle_schema(file_path: &str) -> parquet_viewer::Result<()> {
     Ok(())
 }
 
-fn handle_metadata(file_path: &str) -> parquet_viewer::Result<()> {
-    let path = Path::new(file_path);
-    let metadata = read_metadata(path)?;
+fn handle_schema_tree(file_path: &str) -> parquet_viewer::Result<()> {
+    let tree = match open_input(file_path)? {
+        Input::File(path) => read_schema_tree(Path::new(&path))?,
+        Input::Stream(reader) => {
+            SchemaNode::from_arrow(&read_ipc_stream(reader, &ReadOptions::default())?.schema())
+        }
+        Input::Buffer(bytes) => read_schema_tree_from_reader(bytes)?,
+    };
+
+    println!("Schema for: {}", file_path);
+    println!("{}", tree.name);
+    print_schema_children(&tree, "");
+
+    Ok(())
+}
+
+fn print_schema_children(node: &SchemaNode, prefix: &str) {
+    for (idx, child) in node.children.iter().enumerate() {
+        let last = idx + 1 == node.children.len();
+        let (branch, indent) = if last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        println!("{prefix}{branch}{}", schema_node_label(child));
+        print_schema_children(child, &format!("{prefix}{indent}"));
+    }
+}
+
+/// Describe a schema field on a single line, e.g.
+/// `name: OPTIONAL BYTE_ARRAY (STRING, UTF8) -> Utf8 id=3`.
+fn schema_node_label(node: &SchemaNode) -> String {
+    let mut label = format!("{}:", node.name);
+    if let Some(repetition) = &node.repetition {
+        label.push_str(&format!(" {repetition}"));
+    }
+    match &node.physical_type {
+        Some(physical_type) => label.push_str(&format!(" {physical_type}")),
+        None if node.arrow_type.is_none() && !node.children.is_empty() => label.push_str(" group"),
+        None => {}
+    }
+
+    let mut annotations: Vec<_> = [&node.logical_type, &node.converted_type]
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .collect();
+    annotations.dedup();
+    if !annotations.is_empty() {
+        label.push_str(&format!(" ({})", annotations.join(", ")));
+    }
+    if let Some(arrow_type) = &node.arrow_type {
+        label.push_str(&format!(" -> {arrow_type}"));
+    }
+    if let Some(field_id) = node.field_id {
+        label.push_str(&format!(" id={field_id}"));
+    }
+    if !node.metadata.is_empty() {
+        let metadata: Vec<_> = node
+            .metadata
+            .iter()
+            .map(|(key, value)| format!("{key}={}", truncate(value)))
+            .collect();
+        label.push_str(&format!(" {{{}}}", metadata.join(", ")));
+    }
+    label
+}
+
+fn handle_metadata(file_path: &str, detailed: bool) -> parquet_viewer::Result<()> {
+    let metadata = match (open_input(file_path)?, detailed) {
+        (Input::File(path), false) => read_metadata(Path::new(&path))?,
+        (Input::File(path), true) => read_detailed_metadata(Path::new(&path))?,
+        // Counting records and bytes needs the whole stream anyway
+        (Input::Stream(reader), false) => read_metadata_from_reader(read_to_bytes(reader)?)?,
+        (Input::Stream(reader), true) => {
+            read_detailed_metadata_from_reader(read_to_bytes(reader)?)?
+        }
+        (Input::Buffer(bytes), false) => read_metadata_from_reader(bytes)?,
+        (Input::Buffer(bytes), true) => read_detailed_metadata_from_reader(bytes)?,
+    };
 
     println!("Metadata for: {}", file_path);
 
@@ -167,8 +669,63 @@ fn handle_metadata(file_path: &str) -> parquet_viewer::Result<()> {
         ]));
     }
 
+    let page_index = match (metadata.has_column_index, metadata.has_offset_index) {
+        (true, true) => "Column index, offset index",
+        (false, true) => "Offset index",
+        (true, false) => "Column index",
+        (false, false) => "None",
+    };
+    table.add_row(Row::new(vec![
+        Cell::new("Page index"),
+        Cell::new(page_index),
+    ]));
+
+    if let Some(ipc) = &metadata.ipc {
+        table.add_row(Row::new(vec![
+            Cell::new("IPC metadata version"),
+            Cell::new(&ipc.metadata_version),
+        ]));
+        table.add_row(Row::new(vec![
+            Cell::new("IPC compression"),
+            Cell::new(ipc.compression.as_deref().unwrap_or("None")),
+        ]));
+    }
+
     table.printstd();
 
+    if let Some(ipc) = &metadata.ipc
+        && !ipc.blocks.is_empty()
+    {
+        println!("\nRecord Batch Blocks:");
+        let mut block_table = Table::new();
+        block_table.add_row(Row::new(vec![
+            Cell::new("Block"),
+            Cell::new("Offset"),
+            Cell::new("Metadata length"),
+            Cell::new("Body length"),
+            Cell::new("Rows"),
+        ]));
+
+        for (idx, block) in ipc.blocks.iter().enumerate() {
+            block_table.add_row(Row::new(vec![
+                Cell::new(&idx.to_string()),
+                Cell::new(&block.offset.to_string()),
+                Cell::new(&block.metadata_length.to_string()),
+                Cell::new(&block.body_length.to_string()),
+                Cell::new(&block.num_rows.to_string()),
+            ]));
+        }
+
+        block_table.printstd();
+    }
+
+    if detailed && let Some(row_groups) = &metadata.row_groups {
+        for (idx, row_group) in row_groups.iter().enumerate() {
+            println!("\nRow Group {}:", idx);
+            row_group_table(row_group).printstd();
+        }
+    }
+
     if let Some(kv_metadata) = metadata.key_value_metadata
         && !kv_metadata.is_empty()
     {
@@ -193,18 +750,254 @@ fn handle_metadata(file_path: &str) -> parquet_viewer::Result<()> {
     Ok(())
 }
 
-fn handle_data(
-    file_path: &str,
-    batch_size: Option<usize>,
-    limit: Option<usize>,
-) -> parquet_viewer::Result<()> {
-    let path = Path::new(file_path);
-    let batches = read_data(path, batch_size, limit)?;
+/// Render a row group, with its column chunks in a nested table.
+fn row_group_table(row_group: &RowGroupDetails) -> Table {
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Rows"),
+        Cell::new("Total byte size"),
+        Cell::new("Compressed size"),
+        Cell::new("File offset"),
+        Cell::new("Sorting columns"),
+    ]));
+
+    let sorting_columns = match &row_group.sorting_columns {
+        Some(sorting_columns) => sorting_columns
+            .iter()
+            .map(|sorting_column| {
+                format!(
+                    "{} {} NULLS {}",
+                    sorting_column.column,
+                    if sorting_column.descending {
+                        "DESC"
+                    } else {
+                        "ASC"
+                    },
+                    if sorting_column.nulls_first {
+                        "FIRST"
+                    } else {
+                        "LAST"
+                    }
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        None => "None".to_string(),
+    };
+    table.add_row(Row::new(vec![
+        Cell::new(&row_group.num_rows.to_string()),
+        Cell::new(&format!("{} bytes", row_group.total_byte_size)),
+        Cell::new(&format!("{} bytes", row_group.compressed_size)),
+        Cell::new(&optional(row_group.file_offset)),
+        Cell::new(&sorting_columns),
+    ]));
+
+    let mut column_table = Table::new();
+    column_table.add_row(Row::new(vec![
+        Cell::new("Column"),
+        Cell::new("Type"),
+        Cell::new("Codec"),
+        Cell::new("Encodings"),
+        Cell::new("Values"),
+        Cell::new("Compressed"),
+        Cell::new("Uncompressed"),
+        Cell::new("Dictionary offset"),
+        Cell::new("Data offset"),
+        Cell::new("Bloom filter"),
+        Cell::new("Min"),
+        Cell::new("Max"),
+        Cell::new("Nulls"),
+        Cell::new("Distinct"),
+    ]));
+    for column in &row_group.columns {
+        let statistics = column.statistics.as_ref();
+        let bound = |value: Option<&String>, exact: bool| match value {
+            Some(value) if exact => truncate(value),
+            Some(value) => format!("{} (inexact)", truncate(value)),
+            None => "-".to_string(),
+        };
+
+        column_table.add_row(Row::new(vec![
+            Cell::new(&column.path),
+            Cell::new(&column.physical_type),
+            Cell::new(&column.codec),
+            Cell::new(&column.encodings.join(", ")),
+            Cell::new(&column.num_values.to_string()),
+            Cell::new(&column.compressed_size.to_string()),
+            Cell::new(&column.uncompressed_size.to_string()),
+            Cell::new(&optional(column.dictionary_page_offset)),
+            Cell::new(&column.data_page_offset.to_string()),
+            Cell::new(
+                &match (column.bloom_filter_offset, column.bloom_filter_length) {
+                    (Some(offset), Some(length)) => format!("{offset} ({length} bytes)"),
+                    (offset, _) => optional(offset),
+                },
+            ),
+            Cell::new(&bound(
+                statistics.and_then(|s| s.min.as_ref()),
+                statistics.is_some_and(|s| s.is_min_exact),
+            )),
+            Cell::new(&bound(
+                statistics.and_then(|s| s.max.as_ref()),
+                statistics.is_some_and(|s| s.is_max_exact),
+            )),
+            Cell::new(&optional(statistics.and_then(|s| s.null_count))),
+            Cell::new(&optional(statistics.and_then(|s| s.distinct_count))),
+        ]));
+    }
+    table.add_row(Row::new(vec![
+        Cell::new(&column_table.to_string()).with_hspan(5),
+    ]));
+
+    table
+}
+
+fn handle_stats(file_path: &str) -> parquet_viewer::Result<()> {
+    let statistics = match open_input(file_path)? {
+        Input::File(path) => read_column_statistics(Path::new(&path))?,
+        // Fails with a clear error, streams have no statistics
+        Input::Stream(reader) => read_column_statistics_from_reader(read_to_bytes(reader)?)?,
+        Input::Buffer(bytes) => read_column_statistics_from_reader(bytes)?,
+    };
+
+    println!("Column statistics for: {}", file_path);
+
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Column"),
+        Cell::new("Type"),
+        Cell::new("Min"),
+        Cell::new("Max"),
+        Cell::new("Nulls"),
+        Cell::new("Distinct"),
+        Cell::new("Issues"),
+    ]));
+
+    for column in &statistics {
+        let bound = |value: Option<&String>, exact: bool| match value {
+            Some(value) if exact => truncate(value),
+            Some(value) => format!("{} (inexact)", truncate(value)),
+            None => "-".to_string(),
+        };
+        let distinct_count = match column.distinct_count {
+            Some(count) if column.num_row_groups > 1 => format!(">= {count}"),
+            count => optional(count),
+        };
+        let issues = column
+            .issues
+            .iter()
+            .map(|issue| issue.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        table.add_row(Row::new(vec![
+            Cell::new(&column.path),
+            Cell::new(&column.physical_type),
+            Cell::new(&bound(column.min.as_ref(), column.is_min_exact)),
+            Cell::new(&bound(column.max.as_ref(), column.is_max_exact)),
+            Cell::new(&optional(column.null_count)),
+            Cell::new(&distinct_count),
+            Cell::new(if issues.is_empty() { "-" } else { &issues }),
+        ]));
+    }
+
+    table.printstd();
+
+    Ok(())
+}
+
+fn handle_audit_stats(file_path: &str, all: bool) -> parquet_viewer::Result<()> {
+    let audits = match open_input(file_path)? {
+        Input::File(path) => audit_statistics(Path::new(&path))?,
+        Input::Stream(reader) => audit_statistics_from_reader(read_to_bytes(reader)?)?,
+        Input::Buffer(bytes) => audit_statistics_from_reader(bytes)?,
+    };
+
+    println!("Statistics audit of: {}", file_path);
+
+    let inconsistent = audits.iter().filter(|audit| !audit.is_consistent()).count();
+    let without_statistics = audits.iter().filter(|audit| !audit.has_statistics).count();
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Row group"),
+        Cell::new("Column"),
+        Cell::new("Stored min"),
+        Cell::new("Actual min"),
+        Cell::new("Stored max"),
+        Cell::new("Actual max"),
+        Cell::new("Stored nulls"),
+        Cell::new("Actual nulls"),
+        Cell::new("Mismatches"),
+    ]));
+    for audit in audits.iter().filter(|audit| all || !audit.is_consistent()) {
+        let bound = |value: Option<&String>| value.map_or("-".to_string(), |v| truncate(v));
+        let mut mismatches = audit
+            .mismatches
+            .iter()
+            .map(|mismatch| mismatch.to_string())
+            .collect::<Vec<_>>();
+        if audit.deprecated_min_max && !audit.is_consistent() {
+            mismatches.push("min/max from the deprecated fields".to_string());
+        }
+        let mismatches = mismatches.join("\n");
+
+        table.add_row(Row::new(vec![
+            Cell::new(&audit.row_group.to_string()),
+            Cell::new(&audit.column),
+            Cell::new(&bound(audit.stored_min.as_ref())),
+            Cell::new(&bound(audit.actual_min.as_ref())),
+            Cell::new(&bound(audit.stored_max.as_ref())),
+            Cell::new(&bound(audit.actual_max.as_ref())),
+            Cell::new(&optional(audit.stored_null_count)),
+            Cell::new(&audit.actual_null_count.to_string()),
+            Cell::new(if mismatches.is_empty() {
+                "-"
+            } else {
+                &mismatches
+            }),
+        ]));
+    }
+    if all || inconsistent > 0 {
+        table.printstd();
+    }
+
+    println!("{} column chunks audited", audits.len());
+    if without_statistics > 0 {
+        println!("{} column chunks without statistics", without_statistics);
+    }
+    if inconsistent == 0 {
+        println!("All stored statistics match the data");
+        return Ok(());
+    }
+    println!("{} column chunks with mismatched statistics", inconsistent);
+    std::process::exit(1);
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "-".to_string(), |value| value.to_string())
+}
+
+/// Shorten long statistics values for display.
+fn truncate(value: &str) -> String {
+    if value.chars().count() > 32 {
+        format!("{}...", value.chars().take(32).collect::<String>())
+    } else {
+        value.to_string()
+    }
+}
+
+fn handle_data(file_path: &str, options: &ReadOptions) -> parquet_viewer::Result<()> {
+    let reader = match open_input(file_path)? {
+        Input::File(path) => read_batches_with_options(Path::new(&path), options)?,
+        Input::Stream(reader) => read_ipc_stream(reader, options)?,
+        Input::Buffer(bytes) => read_batches_from_reader(bytes, options)?,
+    };
 
     println!("Data from: {}", file_path);
 
     let mut total_rows = 0;
-    for (batch_idx, batch) in batches.iter().enumerate() {
+    for (batch_idx, batch) in reader.enumerate() {
+        let batch = batch?;
         println!(
             "\nBatch {}: {} rows x {} columns",
             batch_idx,
@@ -212,7 +1005,7 @@ fn handle_data(
             batch.num_columns()
         );
 
-        let rows_to_print = if let Some(limit) = limit {
+        let rows_to_print = if let Some(limit) = options.limit {
             std::cmp::min(batch.num_rows(), limit - total_rows)
         } else {
             std::cmp::min(batch.num_rows(), 10)
@@ -301,6 +1094,470 @@ fn handle_data(
     Ok(())
 }
 
+fn handle_profile(file_path: &str, options: &ReadOptions) -> parquet_viewer::Result<()> {
+    let reader = match open_input(file_path)? {
+        Input::File(path) => read_batches_with_options(Path::new(&path), options)?,
+        Input::Stream(reader) => read_ipc_stream(reader, options)?,
+        Input::Buffer(bytes) => read_batches_from_reader(bytes, options)?,
+    };
+    let profiles = profile_batches(reader)?;
+
+    println!("Profile of: {}", file_path);
+
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Column"),
+        Cell::new("Type"),
+        Cell::new("Rows"),
+        Cell::new("Nulls"),
+        Cell::new("Distinct (approx)"),
+        Cell::new("Min"),
+        Cell::new("Max"),
+        Cell::new("Mean"),
+        Cell::new("Std dev"),
+        Cell::new("25%"),
+        Cell::new("50%"),
+        Cell::new("75%"),
+    ]));
+    for profile in &profiles {
+        let values = profile.values.as_ref();
+        let quantile = |i: usize| optional(values.map(|v| format_float(v.quantiles[i].1)));
+        table.add_row(Row::new(vec![
+            Cell::new(&profile.name),
+            Cell::new(&truncate(&profile.data_type)),
+            Cell::new(&profile.row_count.to_string()),
+            Cell::new(&format!(
+                "{} ({:.1}%)",
+                profile.null_count,
+                profile.null_percentage()
+            )),
+            Cell::new(&optional(profile.approx_distinct)),
+            Cell::new(&optional(profile.min.as_deref().map(truncate))),
+            Cell::new(&optional(profile.max.as_deref().map(truncate))),
+            Cell::new(&optional(values.map(|v| format_float(v.mean)))),
+            Cell::new(&optional(values.and_then(|v| v.stddev).map(format_float))),
+            Cell::new(&quantile(0)),
+            Cell::new(&quantile(1)),
+            Cell::new(&quantile(2)),
+        ]));
+    }
+    table.printstd();
+
+    if profiles.iter().any(|profile| profile.lengths.is_some()) {
+        println!("\nLengths:");
+        let mut length_table = Table::new();
+        length_table.add_row(Row::new(vec![
+            Cell::new("Column"),
+            Cell::new("Min"),
+            Cell::new("Max"),
+            Cell::new("Mean"),
+            Cell::new("25%"),
+            Cell::new("50%"),
+            Cell::new("75%"),
+        ]));
+        for profile in &profiles {
+            let Some(lengths) = &profile.lengths else {
+                continue;
+            };
+            length_table.add_row(Row::new(vec![
+                Cell::new(&profile.name),
+                Cell::new(&format_float(lengths.min)),
+                Cell::new(&format_float(lengths.max)),
+                Cell::new(&format_float(lengths.mean)),
+                Cell::new(&format_float(lengths.quantiles[0].1)),
+                Cell::new(&format_float(lengths.quantiles[1].1)),
+                Cell::new(&format_float(lengths.quantiles[2].1)),
+            ]));
+        }
+        length_table.printstd();
+    }
+
+    Ok(())
+}
+
+fn handle_top(
+    file_path: &str,
+    column: &str,
+    k: usize,
+    options: &ReadOptions,
+) -> parquet_viewer::Result<()> {
+    let counts = match open_input(file_path)? {
+        Input::File(path) => top_values(Path::new(&path), column, k, options)?,
+        // Value counts need random access to the file
+        Input::Stream(reader) => {
+            top_values_from_reader(read_to_bytes(reader)?, column, k, options)?
+        }
+        Input::Buffer(bytes) => top_values_from_reader(bytes, column, k, options)?,
+    };
+
+    println!("Top values of '{}' in: {}", counts.column, file_path);
+
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Value"),
+        Cell::new("Count"),
+        Cell::new("Percent"),
+    ]));
+    let percent = |count: u64| {
+        if counts.row_count == 0 {
+            0.0
+        } else {
+            count as f64 * 100.0 / counts.row_count as f64
+        }
+    };
+    for value in &counts.values {
+        table.add_row(Row::new(vec![
+            Cell::new(&truncate(&value.value)),
+            Cell::new(&value.count.to_string()),
+            Cell::new(&format!("{:.2}%", percent(value.count))),
+        ]));
+    }
+    if counts.null_count > 0 {
+        table.add_row(Row::new(vec![
+            Cell::new("NULL"),
+            Cell::new(&counts.null_count.to_string()),
+            Cell::new(&format!("{:.2}%", percent(counts.null_count))),
+        ]));
+    }
+    table.printstd();
+
+    println!("\nRows scanned: {}", counts.row_count);
+    if !counts.is_exact() {
+        println!(
+            "Approximate counts: the column has too many distinct values, counts may be up to {} too low",
+            counts.max_error
+        );
+    }
+
+    Ok(())
+}
+
+fn handle_validate(file_path: &str) -> parquet_viewer::Result<()> {
+    let report = match open_input(file_path)? {
+        Input::File(path) => validate_file(Path::new(&path))?,
+        Input::Stream(reader) => validate_from_reader(read_to_bytes(reader)?)?,
+        Input::Buffer(bytes) => validate_from_reader(bytes)?,
+    };
+
+    println!("Validation of: {}", file_path);
+
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![Cell::new("Property"), Cell::new("Value")]));
+    table.add_row(Row::new(vec![
+        Cell::new("File size"),
+        Cell::new(&format!("{} bytes", report.file_size)),
+    ]));
+    table.add_row(Row::new(vec![
+        Cell::new("Row groups"),
+        Cell::new(&report.num_row_groups.to_string()),
+    ]));
+    table.add_row(Row::new(vec![
+        Cell::new("Column chunks decoded"),
+        Cell::new(&report.column_chunks_checked.to_string()),
+    ]));
+    table.add_row(Row::new(vec![
+        Cell::new("Pages"),
+        Cell::new(&format!(
+            "{} ({} with CRC)",
+            report.pages_checked, report.pages_with_crc
+        )),
+    ]));
+    table.printstd();
+
+    if report.is_valid() {
+        println!("No problems found");
+        return Ok(());
+    }
+
+    println!("\n{} problems found:", report.issues.len());
+    for issue in &report.issues {
+        println!("- {}", issue);
+    }
+    std::process::exit(1);
+}
+
+fn handle_recover(
+    file_path: &str,
+    output_path: &str,
+    schema_hint: Option<&str>,
+) -> parquet_viewer::Result<()> {
+    let schema_hint = schema_hint.map(Path::new);
+    let report = match open_input(file_path)? {
+        Input::File(path) => recover_file(Path::new(&path), Path::new(output_path), schema_hint)?,
+        Input::Stream(reader) => {
+            recover_to_file(read_to_bytes(reader)?, Path::new(output_path), schema_hint)?
+        }
+        Input::Buffer(bytes) => recover_to_file(bytes, Path::new(output_path), schema_hint)?,
+    };
+
+    println!("Recovery of: {}", file_path);
+
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![Cell::new("Property"), Cell::new("Value")]));
+    table.add_row(Row::new(vec![
+        Cell::new("File size"),
+        Cell::new(&format!("{} bytes", report.file_size)),
+    ]));
+    table.add_row(Row::new(vec![
+        Cell::new("Footer"),
+        Cell::new(if report.footer_intact {
+            "intact"
+        } else {
+            "missing, pages scanned"
+        }),
+    ]));
+    table.add_row(Row::new(vec![
+        Cell::new("Recovered row groups"),
+        Cell::new(&report.recovered_row_groups.to_string()),
+    ]));
+    table.add_row(Row::new(vec![
+        Cell::new("Recovered rows"),
+        Cell::new(&report.recovered_rows.to_string()),
+    ]));
+    table.add_row(Row::new(vec![
+        Cell::new("Unread trailing bytes"),
+        Cell::new(&report.trailing_bytes.to_string()),
+    ]));
+    table.printstd();
+
+    println!("Written to: {}", output_path);
+    if report.lost_row_groups.is_empty() {
+        println!("No row groups lost");
+        return Ok(());
+    }
+
+    println!("\n{} row groups lost:", report.lost_row_groups.len());
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Row group"),
+        Cell::new("Offset"),
+        Cell::new("Length"),
+        Cell::new("Rows"),
+        Cell::new("Reason"),
+    ]));
+    for lost in &report.lost_row_groups {
+        table.add_row(Row::new(vec![
+            Cell::new(&lost.row_group.to_string()),
+            Cell::new(&lost.offset.to_string()),
+            Cell::new(&lost.length.to_string()),
+            Cell::new(
+                &lost
+                    .num_rows
+                    .map_or("unknown".to_string(), |rows| rows.to_string()),
+            ),
+            Cell::new(&lost.reason),
+        ]));
+    }
+    table.printstd();
+    Ok(())
+}
+
+fn handle_probe(file_path: &str, column: &str, value: &str) -> parquet_viewer::Result<()> {
+    let probes = match open_input(file_path)? {
+        Input::File(path) => probe_bloom_filters(Path::new(&path), column, value)?,
+        Input::Stream(reader) => {
+            probe_bloom_filters_from_reader(read_to_bytes(reader)?, column, value)?
+        }
+        Input::Buffer(bytes) => probe_bloom_filters_from_reader(bytes, column, value)?,
+    };
+
+    println!("Probing {} for {} in: {}", column, value, file_path);
+
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Row group"),
+        Cell::new("Rows"),
+        Cell::new("Bloom filter"),
+    ]));
+    for probe in &probes {
+        table.add_row(Row::new(vec![
+            Cell::new(&probe.row_group.to_string()),
+            Cell::new(&probe.num_rows.to_string()),
+            Cell::new(&probe.check.to_string()),
+        ]));
+    }
+    table.printstd();
+
+    let candidates = probes
+        .iter()
+        .filter(|probe| probe.check != BloomFilterCheck::Absent)
+        .count();
+    println!(
+        "{} of {} row groups may contain the value",
+        candidates,
+        probes.len()
+    );
+
+    Ok(())
+}
+
+fn handle_inspect_levels(
+    file_path: &str,
+    column: Option<&str>,
+    row_group: Option<usize>,
+    limit: usize,
+) -> parquet_viewer::Result<()> {
+    println!("Levels of: {}", file_path);
+    match open_input(file_path)? {
+        Input::File(path) => print_levels(
+            &ParquetViewerFile::open(Path::new(&path))?,
+            column,
+            row_group,
+            limit,
+        ),
+        Input::Stream(reader) => print_levels(
+            &ParquetViewerFile::try_new(read_to_bytes(reader)?)?,
+            column,
+            row_group,
+            limit,
+        ),
+        Input::Buffer(bytes) => print_levels(
+            &ParquetViewerFile::try_new(bytes)?,
+            column,
+            row_group,
+            limit,
+        ),
+    }
+}
+
+fn print_levels<R: ChunkReader + 'static>(
+    file: &ParquetViewerFile<R>,
+    column: Option<&str>,
+    row_group: Option<usize>,
+    limit: usize,
+) -> parquet_viewer::Result<()> {
+    let leaves = file.leaf_columns()?;
+
+    let Some(column) = column else {
+        let mut table = Table::new();
+        table.add_row(Row::new(vec![
+            Cell::new("Column"),
+            Cell::new("Type"),
+            Cell::new("Max definition level"),
+            Cell::new("Max repetition level"),
+        ]));
+        for leaf in &leaves {
+            table.add_row(Row::new(vec![
+                Cell::new(&leaf.path),
+                Cell::new(&leaf.physical_type),
+                Cell::new(&leaf.max_def_level.to_string()),
+                Cell::new(&leaf.max_rep_level.to_string()),
+            ]));
+        }
+        table.printstd();
+        return Ok(());
+    };
+
+    let levels = file.read_levels(column, row_group, limit)?;
+    if let Some(leaf) = leaves.iter().find(|leaf| leaf.path == column) {
+        println!(
+            "Column {}: max definition level {}, max repetition level {}",
+            leaf.path, leaf.max_def_level, leaf.max_rep_level
+        );
+    }
+
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Row group"),
+        Cell::new("Repetition level"),
+        Cell::new("Definition level"),
+        Cell::new("Value"),
+    ]));
+    for level in &levels {
+        table.add_row(Row::new(vec![
+            Cell::new(&level.row_group.to_string()),
+            Cell::new(&level.rep_level.to_string()),
+            Cell::new(&level.def_level.to_string()),
+            Cell::new(&optional(level.value.as_deref().map(truncate))),
+        ]));
+    }
+    table.printstd();
+
+    Ok(())
+}
+
+fn handle_inspect_pages(
+    file_path: &str,
+    column: &str,
+    row_group: Option<usize>,
+) -> parquet_viewer::Result<()> {
+    let pages = match open_input(file_path)? {
+        Input::File(path) => {
+            ParquetViewerFile::open(Path::new(&path))?.read_pages(column, row_group)?
+        }
+        Input::Stream(reader) => {
+            ParquetViewerFile::try_new(read_to_bytes(reader)?)?.read_pages(column, row_group)?
+        }
+        Input::Buffer(bytes) => ParquetViewerFile::try_new(bytes)?.read_pages(column, row_group)?,
+    };
+
+    println!("Pages of column {} in: {}", column, file_path);
+
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Row group"),
+        Cell::new("Offset"),
+        Cell::new("Type"),
+        Cell::new("Encoding"),
+        Cell::new("Values"),
+        Cell::new("Rows"),
+        Cell::new("Compressed"),
+        Cell::new("Uncompressed"),
+        Cell::new("CRC"),
+        Cell::new("Min"),
+        Cell::new("Max"),
+        Cell::new("Nulls"),
+    ]));
+
+    for page in &pages {
+        let statistics = page.statistics.as_ref();
+        let null_count = page
+            .num_nulls
+            .map(u64::from)
+            .or_else(|| statistics.and_then(|s| s.null_count));
+        table.add_row(Row::new(vec![
+            Cell::new(&page.row_group.to_string()),
+            Cell::new(&page.offset.to_string()),
+            Cell::new(&page.page_type),
+            Cell::new(&page.encoding),
+            Cell::new(&page.num_values.to_string()),
+            Cell::new(&optional(page.num_rows)),
+            Cell::new(&page.compressed_size.to_string()),
+            Cell::new(&page.uncompressed_size.to_string()),
+            Cell::new(&optional(page.crc.map(|crc| format!("{crc:08x}")))),
+            Cell::new(&optional(
+                statistics.and_then(|s| s.min.as_deref()).map(truncate),
+            )),
+            Cell::new(&optional(
+                statistics.and_then(|s| s.max.as_deref()).map(truncate),
+            )),
+            Cell::new(&optional(null_count)),
+        ]));
+    }
+    table.printstd();
+
+    let compressed: usize = pages
+        .iter()
+        .map(|p| p.header_size + p.compressed_size)
+        .sum();
+    println!(
+        "{} pages, {} bytes including headers",
+        pages.len(),
+        compressed
+    );
+
+    Ok(())
+}
+
+/// Render a number with at most three decimals.
+fn format_float(value: f64) -> String {
+    let value = format!("{value:.3}");
+    value
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
 fn handle_sql(query: &str, style: &str) -> parquet_viewer::Result<()> {
     // Handle reading from stdin if query is "-"
     let sql_input = if query == "-" {
@@ -0,0 +1,158 @@
+use crate::inspect::leaf_index;
+use crate::{ParquetViewerError, Result};
+use parquet::basic::{LogicalType, Type as PhysicalType};
+use parquet::file::metadata::ParquetMetaData;
+use parquet::file::properties::ReaderProperties;
+use parquet::file::reader::{ChunkReader, RowGroupReader};
+use parquet::file::serialized_reader::SerializedRowGroupReader;
+use parquet::schema::types::ColumnDescriptor;
+use std::fmt;
+use std::sync::Arc;
+
+/// Whether a row group may hold a value, according to the bloom filter of
+/// its column chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BloomFilterCheck {
+    /// The bloom filter rules the value out.
+    Absent,
+    /// The value may be present, bloom filters have false positives.
+    MaybePresent,
+    /// The column chunk has no bloom filter, so the row group has to be read.
+    NoBloomFilter,
+}
+
+impl fmt::Display for BloomFilterCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Absent => write!(f, "absent"),
+            Self::MaybePresent => write!(f, "maybe present"),
+            Self::NoBloomFilter => write!(f, "no bloom filter"),
+        }
+    }
+}
+
+/// Result of probing the bloom filter of one row group, see
+/// [`ParquetViewerFile::probe`](crate::ParquetViewerFile::probe).
+#[derive(Debug, Clone)]
+pub struct RowGroupProbe {
+    pub row_group: usize,
+    pub num_rows: i64,
+    pub check: BloomFilterCheck,
+}
+
+/// Check the bloom filter of a leaf column in every row group for `value`.
+pub(crate) fn probe_bloom_filters<R: ChunkReader + 'static>(
+    reader: &Arc<R>,
+    metadata: &ParquetMetaData,
+    path: &str,
+    value: &str,
+) -> Result<Vec<RowGroupProbe>> {
+    let column = leaf_index(metadata, path)?;
+    let descr = metadata.file_metadata().schema_descr().column(column);
+    let value = encode_value(&descr, value)?;
+
+    let props = Arc::new(
+        ReaderProperties::builder()
+            .set_read_bloom_filter(true)
+            .build(),
+    );
+    metadata
+        .row_groups()
+        .iter()
+        .enumerate()
+        .map(|(index, row_group)| {
+            let check = if row_group.column(column).bloom_filter_offset().is_none() {
+                BloomFilterCheck::NoBloomFilter
+            } else {
+                let reader =
+                    SerializedRowGroupReader::new(reader.clone(), row_group, None, props.clone())?;
+                match reader.get_column_bloom_filter(column) {
+                    Some(filter) if filter.check(&value) => BloomFilterCheck::MaybePresent,
+                    Some(_) => BloomFilterCheck::Absent,
+                    None => BloomFilterCheck::NoBloomFilter,
+                }
+            };
+            Ok(RowGroupProbe {
+                row_group: index,
+                num_rows: row_group.num_rows(),
+                check,
+            })
+        })
+        .collect()
+}
+
+/// Plain encoding of a value given as text, which is what bloom filters hash.
+fn encode_value(column: &ColumnDescriptor, value: &str) -> Result<Vec<u8>> {
+    let invalid = || {
+        ParquetViewerError::InvalidValue(format!(
+            "'{value}' is not a valid {} value for column {}",
+            column.physical_type(),
+            column.path().string()
+        ))
+    };
+    let unsigned = matches!(
+        column.logical_type(),
+        Some(LogicalType::Integer {
+            is_signed: false,
+            ..
+        })
+    );
+
+    let bytes = match column.physical_type() {
+        PhysicalType::BOOLEAN => vec![value.parse::<bool>().map_err(|_| invalid())? as u8],
+        PhysicalType::INT32 if unsigned => value
+            .parse::<u32>()
+            .map_err(|_| invalid())?
+            .to_le_bytes()
+            .to_vec(),
+        PhysicalType::INT32 => value
+            .parse::<i32>()
+            .map_err(|_| invalid())?
+            .to_le_bytes()
+            .to_vec(),
+        PhysicalType::INT64 if unsigned => value
+            .parse::<u64>()
+            .map_err(|_| invalid())?
+            .to_le_bytes()
+            .to_vec(),
+        PhysicalType::INT64 => value
+            .parse::<i64>()
+            .map_err(|_| invalid())?
+            .to_le_bytes()
+            .to_vec(),
+        PhysicalType::FLOAT => value
+            .parse::<f32>()
+            .map_err(|_| invalid())?
+            .to_le_bytes()
+            .to_vec(),
+        PhysicalType::DOUBLE => value
+            .parse::<f64>()
+            .map_err(|_| invalid())?
+            .to_le_bytes()
+            .to_vec(),
+        PhysicalType::BYTE_ARRAY => value.as_bytes().to_vec(),
+        PhysicalType::FIXED_LEN_BYTE_ARRAY => {
+            let length = column.type_length() as usize;
+            if value.len() == length {
+                value.as_bytes().to_vec()
+            } else {
+                // Hex, such as UUIDs with or without dashes
+                decode_hex(&value.replace('-', ""))
+                    .filter(|bytes| bytes.len() == length)
+                    .ok_or_else(invalid)?
+            }
+        }
+        PhysicalType::INT96 => return Err(invalid()),
+    };
+    Ok(bytes)
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
@@ -0,0 +1,404 @@
+use crate::{BatchReader, Result};
+use arrow::array::{Array, ArrayRef, AsArray, RecordBatch, make_array};
+use arrow::buffer::NullBuffer;
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Float64Type};
+use arrow::row::{OwnedRow, RowConverter, SortField};
+use arrow::util::display::array_value_to_string;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// Quantiles reported for numeric values and lengths.
+const QUANTILES: [f64; 3] = [0.25, 0.5, 0.75];
+
+/// Number of values kept to estimate quantiles.
+const SAMPLE_SIZE: usize = 10_000;
+
+/// Number of bits of the hash selecting a HyperLogLog register, giving a
+/// standard error of about 0.8%.
+const HLL_PRECISION: u32 = 14;
+
+/// Profile of a column computed by scanning its data, see [`profile_batches`].
+#[derive(Debug, Clone)]
+pub struct ColumnProfile {
+    /// Column name, fields of struct columns as dotted paths.
+    pub name: String,
+    pub data_type: String,
+    pub row_count: u64,
+    pub null_count: u64,
+    /// Smallest and largest values, for columns that are not nested.
+    pub min: Option<String>,
+    pub max: Option<String>,
+    /// Approximate number of distinct non-null values, from a HyperLogLog
+    /// sketch.
+    pub approx_distinct: Option<u64>,
+    /// Distribution of the values of numeric columns.
+    pub values: Option<Distribution>,
+    /// Distribution of the lengths of string, binary and list values, in
+    /// characters, bytes and elements respectively.
+    pub lengths: Option<Distribution>,
+}
+
+impl ColumnProfile {
+    pub fn null_percentage(&self) -> f64 {
+        if self.row_count == 0 {
+            return 0.0;
+        }
+        self.null_count as f64 * 100.0 / self.row_count as f64
+    }
+}
+
+/// Summary of a set of numbers, ignoring nulls and NaN.
+#[derive(Debug, Clone)]
+pub struct Distribution {
+    pub count: u64,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    /// Sample standard deviation, `None` for fewer than two values.
+    pub stddev: Option<f64>,
+    /// Approximate 25th, 50th and 75th percentiles as `(quantile, value)`,
+    /// estimated from a uniform sample of the values.
+    pub quantiles: Vec<(f64, f64)>,
+}
+
+/// Scan every batch of a reader once and profile each of its columns.
+///
+/// Struct columns are profiled field by field.
+pub fn profile_batches(reader: BatchReader) -> Result<Vec<ColumnProfile>> {
+    let schema = reader.schema();
+    let mut columns = Vec::new();
+    for (index, field) in schema.fields().iter().enumerate() {
+        ColumnProfiler::collect(field.name(), vec![index], field.data_type(), &mut columns)?;
+    }
+
+    for batch in reader {
+        let batch = batch?;
+        for column in &mut columns {
+            column.update(&column_array(&batch, &column.path)?)?;
+        }
+    }
+
+    columns.into_iter().map(ColumnProfiler::finish).collect()
+}
+
+/// Extract a column, or a field nested in struct columns, following `path`.
+///
+/// Nested fields are null wherever one of their parents is null.
+pub(crate) fn column_array(batch: &RecordBatch, path: &[usize]) -> Result<ArrayRef> {
+    let mut array = batch.column(path[0]).clone();
+    for &index in &path[1..] {
+        let parent = array.as_struct();
+        let child = parent.column(index);
+        let nulls = NullBuffer::union(parent.nulls(), child.nulls());
+        array = make_array(child.to_data().into_builder().nulls(nulls).build()?);
+    }
+    Ok(array)
+}
+
+struct ColumnProfiler {
+    name: String,
+    path: Vec<usize>,
+    data_type: DataType,
+    row_count: u64,
+    null_count: u64,
+    /// Order preserving encoding of the values, used to find the min and
+    /// max and to hash values of any type. `None` if the type is unsupported.
+    converter: Option<RowConverter>,
+    min: Option<OwnedRow>,
+    max: Option<OwnedRow>,
+    distinct: HyperLogLog,
+    values: Option<DistributionBuilder>,
+    lengths: Option<DistributionBuilder>,
+}
+
+impl ColumnProfiler {
+    /// Add profilers for a column, or for each field of a struct column.
+    fn collect(
+        name: &str,
+        path: Vec<usize>,
+        data_type: &DataType,
+        columns: &mut Vec<ColumnProfiler>,
+    ) -> Result<()> {
+        if let DataType::Struct(fields) = data_type {
+            for (index, field) in fields.iter().enumerate() {
+                let mut path = path.clone();
+                path.push(index);
+                let name = format!("{name}.{}", field.name());
+                Self::collect(&name, path, field.data_type(), columns)?;
+            }
+            return Ok(());
+        }
+
+        let sort_field = SortField::new(data_type.clone());
+        let converter = if RowConverter::supports_fields(std::slice::from_ref(&sort_field)) {
+            Some(RowConverter::new(vec![sort_field])?)
+        } else {
+            None
+        };
+
+        columns.push(ColumnProfiler {
+            name: name.to_string(),
+            path,
+            data_type: data_type.clone(),
+            row_count: 0,
+            null_count: 0,
+            converter,
+            min: None,
+            max: None,
+            distinct: HyperLogLog::new(),
+            values: data_type.is_numeric().then(DistributionBuilder::new),
+            lengths: has_length(data_type).then(DistributionBuilder::new),
+        });
+        Ok(())
+    }
+
+    fn update(&mut self, array: &ArrayRef) -> Result<()> {
+        let nulls = array.logical_nulls();
+        self.row_count += array.len() as u64;
+        self.null_count += nulls.as_ref().map_or(0, |nulls| nulls.null_count()) as u64;
+        let is_valid = |i: usize| nulls.as_ref().is_none_or(|nulls| nulls.is_valid(i));
+
+        if let Some(converter) = &self.converter {
+            let rows = converter.convert_columns(std::slice::from_ref(array))?;
+            let ordered = !self.data_type.is_nested();
+            for (i, row) in rows.iter().enumerate() {
+                if !is_valid(i) {
+                    continue;
+                }
+                let mut hasher = DefaultHasher::new();
+                row.as_ref().hash(&mut hasher);
+                self.distinct.insert(hasher.finish());
+
+                if ordered {
+                    if self.min.as_ref().is_none_or(|min| row < min.row()) {
+                        self.min = Some(row.owned());
+                    }
+                    if self.max.as_ref().is_none_or(|max| row > max.row()) {
+                        self.max = Some(row.owned());
+                    }
+                }
+            }
+        }
+
+        if let Some(values) = &mut self.values {
+            let array = cast(array, &DataType::Float64)?;
+            for value in array.as_primitive::<Float64Type>().iter().flatten() {
+                values.insert(value);
+            }
+        }
+
+        if let Some(lengths) = &mut self.lengths {
+            for_each_length(array, |length| lengths.insert(length as f64));
+        }
+
+        Ok(())
+    }
+
+    fn finish(self) -> Result<ColumnProfile> {
+        let render = |row: Option<OwnedRow>| -> Result<Option<String>> {
+            let (Some(converter), Some(row)) = (&self.converter, row) else {
+                return Ok(None);
+            };
+            let arrays = converter.convert_rows([row.row()])?;
+            Ok(Some(array_value_to_string(&arrays[0], 0)?))
+        };
+
+        Ok(ColumnProfile {
+            min: render(self.min.clone())?,
+            max: render(self.max.clone())?,
+            approx_distinct: self.converter.is_some().then(|| self.distinct.estimate()),
+            values: self.values.and_then(DistributionBuilder::finish),
+            lengths: self.lengths.and_then(DistributionBuilder::finish),
+            name: self.name,
+            data_type: self.data_type.to_string(),
+            row_count: self.row_count,
+            null_count: self.null_count,
+        })
+    }
+}
+
+fn has_length(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Utf8
+            | DataType::LargeUtf8
+            | DataType::Utf8View
+            | DataType::Binary
+            | DataType::LargeBinary
+            | DataType::BinaryView
+            | DataType::List(_)
+            | DataType::LargeList(_)
+    )
+}
+
+/// Call `f` with the length of every non-null value of a column accepted by
+/// [`has_length`].
+fn for_each_length(array: &ArrayRef, mut f: impl FnMut(usize)) {
+    match array.data_type() {
+        DataType::Utf8 => array
+            .as_string::<i32>()
+            .iter()
+            .flatten()
+            .for_each(|s| f(s.chars().count())),
+        DataType::LargeUtf8 => array
+            .as_string::<i64>()
+            .iter()
+            .flatten()
+            .for_each(|s| f(s.chars().count())),
+        DataType::Utf8View => array
+            .as_string_view()
+            .iter()
+            .flatten()
+            .for_each(|s| f(s.chars().count())),
+        DataType::Binary => array
+            .as_binary::<i32>()
+            .iter()
+            .flatten()
+            .for_each(|b| f(b.len())),
+        DataType::LargeBinary => array
+            .as_binary::<i64>()
+            .iter()
+            .flatten()
+            .for_each(|b| f(b.len())),
+        DataType::BinaryView => array
+            .as_binary_view()
+            .iter()
+            .flatten()
+            .for_each(|b| f(b.len())),
+        DataType::List(_) => array
+            .as_list::<i32>()
+            .iter()
+            .flatten()
+            .for_each(|l| f(l.len())),
+        DataType::LargeList(_) => array
+            .as_list::<i64>()
+            .iter()
+            .flatten()
+            .for_each(|l| f(l.len())),
+        _ => {}
+    }
+}
+
+/// Accumulates moments with Welford's algorithm, and a reservoir sample of
+/// the values for quantiles.
+struct DistributionBuilder {
+    count: u64,
+    min: f64,
+    max: f64,
+    mean: f64,
+    m2: f64,
+    sample: Vec<f64>,
+    /// State of a xorshift generator, fixed so that profiles are reproducible.
+    random: u64,
+}
+
+impl DistributionBuilder {
+    fn new() -> Self {
+        Self {
+            count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            mean: 0.0,
+            m2: 0.0,
+            sample: Vec::new(),
+            random: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+
+    fn insert(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+
+        if self.sample.len() < SAMPLE_SIZE {
+            self.sample.push(value);
+        } else {
+            let slot = (self.next_random() % self.count) as usize;
+            if slot < SAMPLE_SIZE {
+                self.sample[slot] = value;
+            }
+        }
+    }
+
+    fn next_random(&mut self) -> u64 {
+        self.random ^= self.random << 13;
+        self.random ^= self.random >> 7;
+        self.random ^= self.random << 17;
+        self.random
+    }
+
+    fn finish(mut self) -> Option<Distribution> {
+        if self.count == 0 {
+            return None;
+        }
+        self.sample.sort_by(f64::total_cmp);
+        let quantiles = QUANTILES
+            .iter()
+            .map(|&q| (q, interpolate(&self.sample, q)))
+            .collect();
+
+        Some(Distribution {
+            count: self.count,
+            min: self.min,
+            max: self.max,
+            mean: self.mean,
+            stddev: (self.count > 1).then(|| (self.m2 / (self.count - 1) as f64).sqrt()),
+            quantiles,
+        })
+    }
+}
+
+/// Quantile of sorted values, interpolating linearly between neighbours.
+fn interpolate(sorted: &[f64], quantile: f64) -> f64 {
+    let position = quantile * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
+}
+
+/// HyperLogLog sketch estimating the number of distinct hashes inserted.
+struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    fn new() -> Self {
+        Self {
+            registers: vec![0; 1 << HLL_PRECISION],
+        }
+    }
+
+    fn insert(&mut self, hash: u64) {
+        let index = (hash >> (64 - HLL_PRECISION)) as usize;
+        // Position of the first set bit in the remaining bits, bounded by a
+        // sentinel bit
+        let rest = (hash << HLL_PRECISION) | (1 << (HLL_PRECISION - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|&rank| 2f64.powi(-(rank as i32)))
+            .sum();
+        let estimate = alpha * m * m / sum;
+
+        // Linear counting is more accurate for small cardinalities
+        let zeros = self.registers.iter().filter(|&&rank| rank == 0).count();
+        if estimate <= 2.5 * m && zeros > 0 {
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            estimate.round() as u64
+        }
+    }
+}
@@ -0,0 +1,436 @@
+use crate::{ParquetViewerError, Result};
+use arrow::array::{Array, ArrayRef, RecordBatch, StructArray};
+use arrow::error::ArrowError;
+use arrow_schema::{DataType, Field, FieldRef, Fields, Schema};
+use parquet::arrow::ProjectionMask;
+use parquet::schema::types::SchemaDescriptor;
+use regex::Regex;
+use std::sync::Arc;
+
+/// Columns to read, resolved against the schema of a file.
+///
+/// Each entry selects a root column, either whole or only some of the fields
+/// of a struct column.
+#[derive(Debug, Clone)]
+pub(crate) struct Projection {
+    fields: Vec<ProjectedField>,
+}
+
+#[derive(Debug, Clone)]
+struct ProjectedField {
+    /// Position of the field within its parent.
+    index: usize,
+    /// Selected children of a struct field, `None` selects the whole field.
+    children: Option<Vec<ProjectedField>>,
+}
+
+impl Projection {
+    /// Select whole root columns, in the given order.
+    pub(crate) fn from_indices(schema: &Schema, indices: &[usize]) -> Result<Self> {
+        let num_columns = schema.fields().len();
+        let fields = indices
+            .iter()
+            .map(|&index| {
+                if index >= num_columns {
+                    return Err(ParquetViewerError::ColumnIndexOutOfRange { index, num_columns });
+                }
+                Ok(ProjectedField {
+                    index,
+                    children: None,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { fields })
+    }
+
+    /// Select columns by name. Fields inside struct columns are selected by
+    /// their dotted path, e.g. `payload.user.id`.
+    ///
+    /// Columns are returned in schema order, whatever the order of `names`.
+    pub(crate) fn from_names<S: AsRef<str>>(schema: &Schema, names: &[S]) -> Result<Self> {
+        let mut projection = Self { fields: Vec::new() };
+        for name in names {
+            let name = name.as_ref();
+            let field =
+                resolve(schema.fields(), name).ok_or_else(|| unknown_column(schema, name))?;
+            merge(&mut projection.fields, field);
+        }
+        projection.sort();
+        Ok(projection)
+    }
+
+    /// Add the columns of `other`, then put all columns in schema order.
+    pub(crate) fn union(mut self, other: Projection) -> Self {
+        for field in other.fields {
+            merge(&mut self.fields, field);
+        }
+        self.sort();
+        self
+    }
+
+    fn sort(&mut self) {
+        sort_fields(&mut self.fields);
+    }
+
+    /// Schema of the batches produced by [`Projection::project_batch`].
+    pub(crate) fn project_schema(&self, schema: &Schema) -> Schema {
+        let fields: Vec<_> = self
+            .fields
+            .iter()
+            .map(|projected| projected.project_field(&schema.fields()[projected.index]))
+            .collect();
+        Schema::new_with_metadata(fields, schema.metadata().clone())
+    }
+
+    /// Extract the selected columns and struct fields from a batch.
+    pub(crate) fn project_batch(
+        &self,
+        batch: &RecordBatch,
+        schema: &Arc<Schema>,
+    ) -> Result<RecordBatch> {
+        let columns = self
+            .fields
+            .iter()
+            .map(|projected| projected.project_array(batch.column(projected.index)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(RecordBatch::try_new(schema.clone(), columns)?)
+    }
+
+    /// Parquet leaf columns covering the selected fields.
+    pub(crate) fn parquet_mask(&self, schema: &Schema, descr: &SchemaDescriptor) -> ProjectionMask {
+        let mut paths = Vec::new();
+        for projected in &self.fields {
+            let field = &schema.fields()[projected.index];
+            projected.collect_paths(field, vec![field.name().as_str()], &mut paths);
+        }
+
+        // Arrow struct fields map to Parquet groups of the same name, so a
+        // selected field covers every leaf under its path
+        let leaves = (0..descr.num_columns()).filter(|&i| {
+            let column = descr.column(i);
+            let parts = column.path().parts();
+            paths.iter().any(|path| {
+                parts.len() >= path.len() && parts.iter().zip(path).all(|(a, b)| a == b)
+            })
+        });
+        ProjectionMask::leaves(descr, leaves)
+    }
+}
+
+impl ProjectedField {
+    fn project_field(&self, field: &FieldRef) -> FieldRef {
+        let (Some(children), DataType::Struct(fields)) = (&self.children, field.data_type()) else {
+            return field.clone();
+        };
+        let fields: Fields = children
+            .iter()
+            .map(|child| child.project_field(&fields[child.index]))
+            .collect();
+        Arc::new(
+            field
+                .as_ref()
+                .clone()
+                .with_data_type(DataType::Struct(fields)),
+        )
+    }
+
+    fn project_array(&self, array: &ArrayRef) -> Result<ArrayRef> {
+        let Some(children) = &self.children else {
+            return Ok(array.clone());
+        };
+        let array = array
+            .as_any()
+            .downcast_ref::<StructArray>()
+            .ok_or_else(|| {
+                ArrowError::SchemaError(format!(
+                    "Expected a struct column, found {}",
+                    array.data_type()
+                ))
+            })?;
+
+        let mut fields = Vec::with_capacity(children.len());
+        let mut columns = Vec::with_capacity(children.len());
+        for child in children {
+            fields.push(child.project_field(&array.fields()[child.index]));
+            columns.push(child.project_array(array.column(child.index))?);
+        }
+        Ok(Arc::new(StructArray::try_new(
+            fields.into(),
+            columns,
+            array.nulls().cloned(),
+        )?))
+    }
+
+    fn collect_paths<'a>(
+        &self,
+        field: &'a Field,
+        path: Vec<&'a str>,
+        paths: &mut Vec<Vec<&'a str>>,
+    ) {
+        match (&self.children, field.data_type()) {
+            (Some(children), DataType::Struct(fields)) => {
+                for child in children {
+                    let field = &fields[child.index];
+                    let mut path = path.clone();
+                    path.push(field.name());
+                    child.collect_paths(field, path, paths);
+                }
+            }
+            _ => paths.push(path),
+        }
+    }
+}
+
+/// Positions of the fields leading from a root column to the field selected
+/// by a name or dotted path.
+pub(crate) fn field_path(schema: &Schema, name: &str) -> Result<Vec<usize>> {
+    let mut field = resolve(schema.fields(), name).ok_or_else(|| unknown_column(schema, name))?;
+    let mut path = vec![field.index];
+    while let Some(mut children) = field.children {
+        field = children.remove(0);
+        path.push(field.index);
+    }
+    Ok(path)
+}
+
+/// Find the field selected by a name or dotted path.
+///
+/// Names that themselves contain dots are matched whole before being split.
+fn resolve(fields: &Fields, path: &str) -> Option<ProjectedField> {
+    if let Some(index) = fields.iter().position(|field| field.name() == path) {
+        return Some(ProjectedField {
+            index,
+            children: None,
+        });
+    }
+
+    fields.iter().enumerate().find_map(|(index, field)| {
+        let rest = path
+            .strip_prefix(field.name().as_str())?
+            .strip_prefix('.')?;
+        let DataType::Struct(children) = field.data_type() else {
+            return None;
+        };
+        Some(ProjectedField {
+            index,
+            children: Some(vec![resolve(children, rest)?]),
+        })
+    })
+}
+
+/// Add a selected field, merging it with an earlier selection of the same
+/// field.
+fn merge(fields: &mut Vec<ProjectedField>, field: ProjectedField) {
+    let Some(existing) = fields.iter_mut().find(|f| f.index == field.index) else {
+        fields.push(field);
+        return;
+    };
+    match (&mut existing.children, field.children) {
+        // Selecting the whole field covers any of its children
+        (None, _) => {}
+        (children @ Some(_), None) => *children = None,
+        (Some(existing), Some(children)) => {
+            for child in children {
+                merge(existing, child);
+            }
+        }
+    }
+}
+
+fn sort_fields(fields: &mut [ProjectedField]) {
+    fields.sort_by_key(|field| field.index);
+    for field in fields {
+        if let Some(children) = &mut field.children {
+            sort_fields(children);
+        }
+    }
+}
+
+/// Resolve column selectors into the names of the columns to read.
+///
+/// A selector is a column name, a dotted path to a field of a struct column,
+/// or `re:<pattern>` to select every column whose name matches a regular
+/// expression. Columns matching an `exclude` selector are removed from the
+/// selection, which is every column when `columns` is `None`. Excluding a
+/// field of a selected struct column keeps its other fields, and a
+/// selection left without any column is an error.
+///
+/// Names are returned in schema order.
+pub fn select_columns<S: AsRef<str>>(
+    schema: &Schema,
+    columns: Option<&[S]>,
+    exclude: &[S],
+) -> Result<Vec<String>> {
+    let mut selected = Vec::new();
+    match columns {
+        Some(columns) => {
+            for selector in columns {
+                for name in match_selector(schema, selector.as_ref())? {
+                    if !selected.contains(&name) {
+                        selected.push(name);
+                    }
+                }
+            }
+        }
+        None => selected.extend(schema.fields().iter().map(|field| field.name().clone())),
+    }
+
+    for selector in exclude {
+        for excluded in match_selector(schema, selector.as_ref())? {
+            selected = selected
+                .into_iter()
+                .flat_map(|name| {
+                    // Excluding a struct column also excludes the fields
+                    // selected within it
+                    if name == excluded || name.starts_with(&format!("{excluded}.")) {
+                        Vec::new()
+                    } else if let Some(rest) = excluded.strip_prefix(&format!("{name}.")) {
+                        fields_without(schema, &name, rest)
+                    } else {
+                        vec![name]
+                    }
+                })
+                .collect();
+        }
+    }
+    if selected.is_empty() {
+        return Err(ParquetViewerError::InvalidColumnSelector(
+            "no columns left to read".to_string(),
+        ));
+    }
+
+    // Order by the position of the root column
+    selected.sort_by_key(|name| {
+        schema.fields().iter().position(|field| {
+            name == field.name() || name.starts_with(&format!("{}.", field.name()))
+        })
+    });
+    Ok(selected)
+}
+
+/// Paths of the fields of the struct column or field `name`, without the
+/// field at the dotted path `rest` below it.
+fn fields_without(schema: &Schema, name: &str, rest: &str) -> Vec<String> {
+    let Some(mut projected) = resolve(schema.fields(), name) else {
+        return vec![name.to_string()];
+    };
+    let mut field = &schema.fields()[projected.index];
+    while let Some(mut children) = projected.children {
+        projected = children.remove(0);
+        let DataType::Struct(fields) = field.data_type() else {
+            break;
+        };
+        field = &fields[projected.index];
+    }
+    let DataType::Struct(fields) = field.data_type() else {
+        return vec![name.to_string()];
+    };
+    let Some(excluded) = resolve(fields, rest) else {
+        return vec![name.to_string()];
+    };
+
+    let mut paths = Vec::new();
+    collect_paths_without(fields, &format!("{name}."), &excluded, &mut paths);
+    paths
+}
+
+fn collect_paths_without(
+    fields: &Fields,
+    prefix: &str,
+    excluded: &ProjectedField,
+    paths: &mut Vec<String>,
+) {
+    for (index, field) in fields.iter().enumerate() {
+        let path = format!("{prefix}{}", field.name());
+        if index != excluded.index {
+            paths.push(path);
+        } else if let (Some(children), DataType::Struct(fields)) =
+            (&excluded.children, field.data_type())
+        {
+            collect_paths_without(fields, &format!("{path}."), &children[0], paths);
+        }
+    }
+}
+
+/// Names selected by a single selector.
+fn match_selector(schema: &Schema, selector: &str) -> Result<Vec<String>> {
+    let Some(pattern) = selector.strip_prefix("re:") else {
+        resolve(schema.fields(), selector).ok_or_else(|| unknown_column(schema, selector))?;
+        return Ok(vec![selector.to_string()]);
+    };
+
+    let regex = Regex::new(pattern)
+        .map_err(|e| ParquetViewerError::InvalidColumnSelector(format!("{selector}: {e}")))?;
+    Ok(schema
+        .fields()
+        .iter()
+        .filter(|field| regex.is_match(field.name()))
+        .map(|field| field.name().clone())
+        .collect())
+}
+
+/// Error for a column missing from `schema`, suggesting columns and nested
+/// fields with a similar name.
+pub(crate) fn unknown_column(schema: &Schema, name: &str) -> ParquetViewerError {
+    let mut paths = Vec::new();
+    collect_field_paths(schema.fields(), "", &mut paths);
+    ParquetViewerError::UnknownColumn {
+        name: name.to_string(),
+        suggestions: similar_names(name, paths),
+    }
+}
+
+/// Up to three candidates close to `name`, closest first.
+pub(crate) fn similar_names(name: &str, candidates: Vec<String>) -> Vec<String> {
+    let target = name.to_lowercase();
+    let max_distance = (name.chars().count() / 3).max(1);
+    let mut candidates: Vec<_> = candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let distance = edit_distance(&target, &candidate.to_lowercase());
+            (distance <= max_distance).then_some((distance, candidate))
+        })
+        .collect();
+    candidates.sort();
+    candidates
+        .into_iter()
+        .take(3)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// Names of all columns, and dotted paths of all fields nested in structs.
+fn collect_field_paths(fields: &Fields, prefix: &str, paths: &mut Vec<String>) {
+    for field in fields {
+        let path = format!("{prefix}{}", field.name());
+        if let DataType::Struct(children) = field.data_type() {
+            collect_field_paths(children, &format!("{path}."), paths);
+        }
+        paths.push(path);
+    }
+}
+
+/// Edit distance between two strings, counting insertions, deletions,
+/// substitutions and transpositions of adjacent characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    d[0] = (0..=b.len()).collect();
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
@@ -0,0 +1,704 @@
+use crate::inspect::{chunk_range, parse_page_header};
+use crate::validate::count_records;
+use crate::{
+    BoundedReader, FileFormat, ParquetViewerError, ParquetViewerFile, Result, SharedReader,
+};
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatch;
+use arrow_schema::SchemaRef;
+use parquet::arrow::arrow_reader::{
+    ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReaderBuilder,
+};
+use parquet::arrow::{ArrowSchemaConverter, ArrowWriter};
+use parquet::basic::{BrotliLevel, Compression, Encoding, GzipLevel, ZstdLevel};
+use parquet::column::reader::get_column_reader;
+use parquet::errors::ParquetError;
+use parquet::file::metadata::{
+    ColumnChunkMetaData, FileMetaData, ParquetMetaData, ParquetMetaDataReader, RowGroupMetaData,
+};
+use parquet::file::properties::{DEFAULT_MAX_ROW_GROUP_SIZE, WriterProperties};
+use parquet::file::reader::{ChunkReader, Length};
+use parquet::file::serialized_reader::SerializedPageReader;
+use parquet::format::{BloomFilterHeader, PageHeader, PageType};
+use parquet::schema::types::{ColumnDescPtr, SchemaDescPtr};
+use parquet::thrift::TSerializable;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use thrift::protocol::TCompactInputProtocol;
+
+/// Bytes read at once when looking for a page header.
+const HEADER_WINDOW: u64 = 64 * 1024;
+
+/// Largest page header accepted, headers with statistics of long values can
+/// be large.
+const MAX_HEADER_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Outcome of recovering a damaged Parquet file, see
+/// [`recover_file`](crate::recover_file).
+#[derive(Debug, Clone)]
+pub struct RecoveryReport {
+    pub file_size: u64,
+    /// Whether row groups were located from the footer, rather than by
+    /// scanning page headers.
+    pub footer_intact: bool,
+    pub recovered_row_groups: usize,
+    pub recovered_rows: u64,
+    pub lost_row_groups: Vec<LostRowGroup>,
+    /// Bytes after the last row group found, such as bloom filters, page
+    /// indexes or a partial footer.
+    pub trailing_bytes: u64,
+}
+
+/// A row group that could not be recovered.
+#[derive(Debug, Clone)]
+pub struct LostRowGroup {
+    /// Position among the row groups found.
+    pub row_group: usize,
+    pub offset: u64,
+    pub length: u64,
+    /// Rows of the row group, once one of its column chunks was complete.
+    pub num_rows: Option<i64>,
+    pub reason: String,
+}
+
+/// Schema and writer settings of a damaged file, taken from a file written
+/// the same way.
+struct SchemaHint {
+    arrow_schema: SchemaRef,
+    parquet_schema: SchemaDescPtr,
+    /// Codec of each leaf column, when the hint is a Parquet file.
+    codecs: Vec<Option<Compression>>,
+    /// Rows of the first row group of the hint, a likely row group size.
+    row_group_size: Option<i64>,
+}
+
+impl SchemaHint {
+    fn open(path: &Path) -> Result<Self> {
+        let file = ParquetViewerFile::open(path)?;
+        let arrow_schema = file.schema();
+        if !matches!(file.format(), FileFormat::Parquet) {
+            let parquet_schema = ArrowSchemaConverter::new().convert(&arrow_schema)?;
+            return Ok(Self {
+                codecs: vec![None; parquet_schema.num_columns()],
+                parquet_schema: Arc::new(parquet_schema),
+                arrow_schema,
+                row_group_size: None,
+            });
+        }
+
+        let metadata = file.parquet_metadata("Schema hints")?;
+        let parquet_schema = metadata.file_metadata().schema_descr_ptr();
+        let first = metadata.row_groups().first();
+        Ok(Self {
+            codecs: (0..parquet_schema.num_columns())
+                .map(|i| first.map(|row_group| row_group.column(i).compression()))
+                .collect(),
+            parquet_schema,
+            arrow_schema,
+            row_group_size: first.map(|row_group| row_group.num_rows()),
+        })
+    }
+}
+
+/// Row groups located in a damaged file.
+struct Located {
+    /// Footer listing only the row groups whose column chunks were found.
+    metadata: ArrowReaderMetadata,
+    /// Position of each row group of the footer among all those found.
+    positions: Vec<usize>,
+    lost: Vec<LostRowGroup>,
+    footer_intact: bool,
+    trailing_bytes: u64,
+}
+
+/// Copy every intact row group of a Parquet file to `output`.
+///
+/// Row groups are located from the footer when it can be read, otherwise by
+/// scanning page headers with the schema of `hint`, a Parquet or Arrow file
+/// written the same way. The `ARROW:schema` of a damaged footer is not
+/// recovered, even from a partially written one, so the hint is required
+/// whenever the footer cannot be read.
+pub(crate) fn recover<R: ChunkReader + 'static, W: Write + Send>(
+    reader: R,
+    output: W,
+    hint: Option<&Path>,
+) -> Result<RecoveryReport> {
+    let reader = Arc::new(BoundedReader(reader));
+    let located = match ParquetMetaDataReader::new().parse_and_finish(reader.as_ref()) {
+        Ok(metadata) => from_footer(metadata)?,
+        Err(error) => {
+            let Some(hint) = hint else {
+                return Err(ParquetViewerError::Unrecoverable(format!(
+                    "the footer cannot be read ({error}), and the schema it embeds is lost with it, a schema hint file is needed to scan the pages"
+                )));
+            };
+            Scanner::new(&reader, SchemaHint::open(hint)?).scan()?
+        }
+    };
+
+    let metadata = located.metadata.metadata();
+    let mut props = WriterProperties::builder()
+        // Keep each recovered row group whole
+        .set_max_row_group_size(
+            metadata
+                .row_groups()
+                .iter()
+                .map(|row_group| usize::try_from(row_group.num_rows()).unwrap_or_default())
+                .fold(DEFAULT_MAX_ROW_GROUP_SIZE, usize::max),
+        );
+    if let Some(row_group) = metadata.row_groups().first() {
+        for column in row_group.columns() {
+            props =
+                props.set_column_compression(column.column_path().clone(), column.compression());
+        }
+    }
+    let props = props.build();
+    let mut writer = ArrowWriter::try_new(output, located.metadata.schema().clone(), Some(props))?;
+
+    let mut report = RecoveryReport {
+        file_size: reader.len(),
+        footer_intact: located.footer_intact,
+        recovered_row_groups: 0,
+        recovered_rows: 0,
+        lost_row_groups: located.lost,
+        trailing_bytes: located.trailing_bytes,
+    };
+    for (index, row_group) in metadata.row_groups().iter().enumerate() {
+        // A footer may hold negative or overflowing offsets and sizes
+        let ranges: Vec<_> = row_group.columns().iter().map(chunk_range).collect();
+        let lost = |reason: String| LostRowGroup {
+            row_group: located.positions[index],
+            offset: ranges
+                .iter()
+                .flatten()
+                .map(|range| range.start)
+                .min()
+                .unwrap_or(0),
+            length: ranges.iter().flatten().fold(0, |length: u64, range| {
+                length.saturating_add(range.end - range.start)
+            }),
+            num_rows: Some(row_group.num_rows()),
+            reason,
+        };
+        if let Some((chunk, _)) = row_group
+            .columns()
+            .iter()
+            .zip(&ranges)
+            .find(|(_, range)| range.as_ref().is_none_or(|range| range.end > reader.len()))
+        {
+            report.lost_row_groups.push(lost(format!(
+                "column {} lies outside the file",
+                chunk.column_path().string()
+            )));
+            continue;
+        }
+        let batches = match read_row_group(&reader, &located.metadata, index) {
+            Ok(batches) => batches,
+            Err(error) => {
+                report.lost_row_groups.push(lost(error.to_string()));
+                continue;
+            }
+        };
+        let num_rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+        if num_rows as i64 != row_group.num_rows() {
+            report.lost_row_groups.push(lost(format!(
+                "decoded {num_rows} rows, expected {}",
+                row_group.num_rows()
+            )));
+            continue;
+        }
+
+        for batch in &batches {
+            writer.write(batch)?;
+        }
+        // Keep the row groups of the original file
+        writer.flush()?;
+        report.recovered_row_groups += 1;
+        report.recovered_rows += num_rows as u64;
+    }
+    writer.close()?;
+
+    report.lost_row_groups.sort_by_key(|lost| lost.row_group);
+    Ok(report)
+}
+
+fn from_footer(metadata: ParquetMetaData) -> Result<Located> {
+    let metadata = ArrowReaderMetadata::try_new(Arc::new(metadata), ArrowReaderOptions::new())?;
+    let num_row_groups = metadata.metadata().num_row_groups();
+    Ok(Located {
+        metadata,
+        positions: (0..num_row_groups).collect(),
+        lost: Vec::new(),
+        footer_intact: true,
+        trailing_bytes: 0,
+    })
+}
+
+/// Decode a whole row group, so that it is only written when intact.
+fn read_row_group<R: ChunkReader + 'static>(
+    reader: &Arc<R>,
+    metadata: &ArrowReaderMetadata,
+    index: usize,
+) -> Result<Vec<RecordBatch>> {
+    let batches = ParquetRecordBatchReaderBuilder::new_with_metadata(
+        SharedReader(reader.clone()),
+        metadata.clone(),
+    )
+    .with_row_groups(vec![index])
+    .build()?
+    .collect::<std::result::Result<_, ArrowError>>()?;
+    Ok(batches)
+}
+
+/// A page found by scanning, at an absolute file offset.
+struct ScannedPage {
+    offset: u64,
+    header: PageHeader,
+    /// Size of the header and the stored page data.
+    size: u64,
+}
+
+impl ScannedPage {
+    fn is_dictionary(&self) -> bool {
+        self.header.type_ == PageType::DICTIONARY_PAGE
+    }
+
+    /// Values of a data page, including nulls.
+    fn num_values(&self) -> i64 {
+        if let Some(header) = &self.header.data_page_header {
+            header.num_values as i64
+        } else if let Some(header) = &self.header.data_page_header_v2 {
+            header.num_values as i64
+        } else {
+            0
+        }
+    }
+
+    /// Rows of a data page, when known without decoding it.
+    fn num_rows(&self, max_rep_level: i16) -> Option<i64> {
+        match &self.header.data_page_header_v2 {
+            Some(header) => Some(header.num_rows as i64),
+            // Without lists every value starts a row
+            None if max_rep_level == 0 => Some(self.num_values()),
+            None => None,
+        }
+    }
+
+    fn encoding(&self) -> Option<Encoding> {
+        let encoding = match &self.header {
+            PageHeader {
+                dictionary_page_header: Some(header),
+                ..
+            } => header.encoding,
+            PageHeader {
+                data_page_header: Some(header),
+                ..
+            } => header.encoding,
+            PageHeader {
+                data_page_header_v2: Some(header),
+                ..
+            } => header.encoding,
+            _ => return None,
+        };
+        Encoding::try_from(encoding).ok()
+    }
+}
+
+/// The pages of a column chunk, once its end was found.
+struct ScannedChunk {
+    end: u64,
+    num_rows: i64,
+    /// Metadata to read the chunk with, or why it does not decode.
+    metadata: std::result::Result<ColumnChunkMetaData, String>,
+}
+
+enum ScannedRowGroup {
+    Complete(RowGroupMetaData),
+    /// A row group whose column chunks were all found, but do not decode.
+    Corrupt(LostRowGroup),
+    /// The data ends, or its layout cannot be followed any further.
+    End(Option<LostRowGroup>),
+}
+
+/// Locates the column chunks of a file without a footer, following page
+/// headers from its start.
+///
+/// Chunks end once they hold as many rows as the first chunk of their row
+/// group. The first chunk ends before the dictionary page starting the next
+/// one, or at the size of the previous row group, so a row group is only
+/// found when either is reliable.
+struct Scanner<'a, R> {
+    reader: &'a Arc<R>,
+    hint: SchemaHint,
+    /// Codec of each leaf column, detected by decoding its first chunk.
+    codecs: Vec<Option<Compression>>,
+    offset: u64,
+}
+
+impl<'a, R: ChunkReader + 'static> Scanner<'a, R> {
+    fn new(reader: &'a Arc<R>, hint: SchemaHint) -> Self {
+        Self {
+            reader,
+            codecs: hint.codecs.clone(),
+            hint,
+            // After the leading magic bytes
+            offset: 4,
+        }
+    }
+
+    fn scan(mut self) -> Result<Located> {
+        let mut row_groups = Vec::new();
+        let mut positions = Vec::new();
+        let mut lost = Vec::new();
+        let mut row_group_size = self.hint.row_group_size;
+
+        loop {
+            self.skip_bloom_filters();
+            let position = row_groups.len() + lost.len();
+            if self.page_at(self.offset).is_none() {
+                if self.page_header_at(self.offset).is_some() {
+                    let column = self.hint.parquet_schema.column(0);
+                    lost.push(LostRowGroup {
+                        row_group: position,
+                        offset: self.offset,
+                        length: self.reader.len() - self.offset,
+                        num_rows: None,
+                        reason: format!(
+                            "column {} is truncated in its first page",
+                            column.path().string()
+                        ),
+                    });
+                }
+                break;
+            }
+            match self.scan_row_group(position, row_group_size) {
+                ScannedRowGroup::Complete(row_group) => {
+                    row_group_size = Some(row_group.num_rows());
+                    positions.push(position);
+                    row_groups.push(row_group);
+                }
+                ScannedRowGroup::Corrupt(row_group) => lost.push(row_group),
+                ScannedRowGroup::End(row_group) => {
+                    lost.extend(row_group);
+                    break;
+                }
+            }
+        }
+
+        let num_rows = row_groups
+            .iter()
+            .map(|row_group| row_group.num_rows())
+            .sum();
+        let file_metadata = FileMetaData::new(
+            1,
+            num_rows,
+            None,
+            None,
+            self.hint.parquet_schema.clone(),
+            None,
+        );
+        let metadata = ParquetMetaData::new(file_metadata, row_groups);
+        let options = ArrowReaderOptions::new().with_schema(self.hint.arrow_schema.clone());
+        Ok(Located {
+            metadata: ArrowReaderMetadata::try_new(Arc::new(metadata), options)?,
+            positions,
+            lost,
+            footer_intact: false,
+            trailing_bytes: self.reader.len() - self.offset,
+        })
+    }
+
+    fn scan_row_group(&mut self, position: usize, row_group_size: Option<i64>) -> ScannedRowGroup {
+        let start = self.offset;
+        let mut num_rows = None;
+        let mut columns = Vec::new();
+        let mut corrupt = None;
+
+        for column in 0..self.hint.parquet_schema.num_columns() {
+            let chunk = match self.scan_chunk(column, num_rows, row_group_size) {
+                Ok(chunk) => chunk,
+                Err(reason) => {
+                    return ScannedRowGroup::End(Some(LostRowGroup {
+                        row_group: position,
+                        offset: start,
+                        length: self.reader.len() - start,
+                        num_rows,
+                        reason,
+                    }));
+                }
+            };
+            self.offset = chunk.end;
+            num_rows = Some(chunk.num_rows);
+            match chunk.metadata {
+                Ok(metadata) => columns.push(metadata),
+                Err(reason) => corrupt = corrupt.or(Some(reason)),
+            }
+        }
+
+        let num_rows = num_rows.unwrap_or_default();
+        let row_group = match corrupt {
+            Some(reason) => Err(reason),
+            None => RowGroupMetaData::builder(self.hint.parquet_schema.clone())
+                .set_num_rows(num_rows)
+                .set_total_byte_size(columns.iter().map(|c| c.uncompressed_size()).sum())
+                .set_column_metadata(columns)
+                .build()
+                .map_err(|error| error.to_string()),
+        };
+        match row_group {
+            Ok(row_group) => ScannedRowGroup::Complete(row_group),
+            Err(reason) => ScannedRowGroup::Corrupt(LostRowGroup {
+                row_group: position,
+                offset: start,
+                length: self.offset - start,
+                num_rows: Some(num_rows),
+                reason,
+            }),
+        }
+    }
+
+    /// Find the pages of the chunk of `column` starting at the current
+    /// offset, holding `num_rows` rows when the size of the row group is
+    /// known.
+    fn scan_chunk(
+        &mut self,
+        column: usize,
+        num_rows: Option<i64>,
+        row_group_size: Option<i64>,
+    ) -> std::result::Result<ScannedChunk, String> {
+        let leaf = self.hint.parquet_schema.column(column);
+        let path = leaf.path().string();
+        let mut offset = self.offset;
+        let mut pages = Vec::new();
+        if let Some(page) = self.page_at(offset).filter(ScannedPage::is_dictionary) {
+            offset += page.size;
+            pages.push(page);
+        }
+
+        let mut rows = 0;
+        let mut data_ends = false;
+        loop {
+            let Some(page) = self.page_at(offset) else {
+                data_ends = true;
+                break;
+            };
+            // A dictionary page starts the next chunk
+            if page.is_dictionary() {
+                break;
+            }
+            offset += page.size;
+            let page_rows = page.num_rows(leaf.max_rep_level());
+            pages.push(page);
+            rows = match page_rows {
+                Some(page_rows) => rows + page_rows,
+                None => self.decode(column, &pages)?.1,
+            };
+            match num_rows {
+                Some(num_rows) if rows == num_rows => break,
+                Some(num_rows) if rows > num_rows => {
+                    return Err(format!(
+                        "column {path} holds more than the {num_rows} rows of its row group"
+                    ));
+                }
+                None if row_group_size == Some(rows) => break,
+                _ => {}
+            }
+        }
+
+        if pages.iter().all(ScannedPage::is_dictionary) {
+            return Err(if data_ends {
+                format!("column {path} is truncated before its data pages")
+            } else {
+                format!("no data pages found for column {path}")
+            });
+        }
+        if num_rows.is_some_and(|num_rows| rows < num_rows) {
+            let reason = if data_ends {
+                "is truncated"
+            } else {
+                "ends early"
+            };
+            return Err(format!(
+                "column {path} {reason}, holding {rows} of {} rows",
+                num_rows.unwrap_or_default()
+            ));
+        }
+
+        let metadata = self.decode(column, &pages).and_then(|(metadata, decoded)| {
+            if decoded == rows {
+                Ok(metadata)
+            } else {
+                Err(format!(
+                    "column {path} decodes to {decoded} rows, its pages list {rows}"
+                ))
+            }
+        });
+        Ok(ScannedChunk {
+            end: offset,
+            num_rows: rows,
+            metadata,
+        })
+    }
+
+    /// Decode the pages of a chunk, trying every codec until one reads all
+    /// of its values, and return its metadata and number of rows.
+    fn decode(
+        &mut self,
+        column: usize,
+        pages: &[ScannedPage],
+    ) -> std::result::Result<(ColumnChunkMetaData, i64), String> {
+        let leaf = self.hint.parquet_schema.column(column);
+        let num_values: i64 = pages.iter().map(ScannedPage::num_values).sum();
+        let candidates = match self.codecs[column] {
+            Some(codec) => vec![codec],
+            None => vec![
+                Compression::UNCOMPRESSED,
+                Compression::SNAPPY,
+                Compression::ZSTD(ZstdLevel::default()),
+                Compression::GZIP(GzipLevel::default()),
+                Compression::LZ4_RAW,
+                Compression::LZ4,
+                Compression::BROTLI(BrotliLevel::default()),
+            ],
+        };
+
+        let mut last_error = None;
+        for codec in candidates {
+            let decoded = chunk_metadata(leaf.clone(), pages, codec).and_then(|metadata| {
+                let page_reader =
+                    SerializedPageReader::new(self.reader.clone(), &metadata, 0, None)?;
+                let (rows, values) =
+                    count_records(get_column_reader(leaf.clone(), Box::new(page_reader)))
+                        .map_err(|error| ParquetError::General(error.to_string()))?;
+                if values != num_values {
+                    return Err(ParquetError::General(format!(
+                        "decoded {values} values, the page headers list {num_values}"
+                    )));
+                }
+                Ok((metadata, rows))
+            });
+            match decoded {
+                Ok(decoded) => {
+                    self.codecs[column] = Some(codec);
+                    return Ok(decoded);
+                }
+                Err(error) => last_error = Some(error),
+            }
+        }
+        Err(format!(
+            "column {} does not decode: {}",
+            leaf.path().string(),
+            last_error
+                .map(|error| error.to_string())
+                .unwrap_or_default()
+        ))
+    }
+
+    /// The page whose header starts at `offset`, if any.
+    fn page_at(&self, offset: u64) -> Option<ScannedPage> {
+        self.page_header_at(offset)
+            .filter(|page| page.offset + page.size <= self.reader.len())
+    }
+
+    /// The page whose header starts at `offset`, even if its data goes past
+    /// the end of the file.
+    fn page_header_at(&self, offset: u64) -> Option<ScannedPage> {
+        let remaining = self.reader.len().checked_sub(offset)?;
+        let (header, header_size) =
+            self.read_header(offset, remaining, |bytes| parse_page_header(bytes).ok())?;
+
+        let plausible = header.compressed_page_size >= 0
+            && header.uncompressed_page_size >= 0
+            && match header.type_ {
+                PageType::DICTIONARY_PAGE => header.dictionary_page_header.is_some(),
+                PageType::DATA_PAGE => header.data_page_header.is_some(),
+                PageType::DATA_PAGE_V2 => header.data_page_header_v2.is_some(),
+                _ => false,
+            };
+        let size = header_size as u64 + header.compressed_page_size as u64;
+        plausible.then_some(ScannedPage {
+            offset,
+            header,
+            size,
+        })
+    }
+
+    /// Move past the bloom filters writers put after each row group.
+    fn skip_bloom_filters(&mut self) {
+        while self.page_at(self.offset).is_none() {
+            let Some(remaining) = self.reader.len().checked_sub(self.offset) else {
+                return;
+            };
+            let Some((header, header_size)) = self.read_header(self.offset, remaining, |bytes| {
+                let mut input = bytes;
+                let mut protocol = TCompactInputProtocol::new(&mut input);
+                let header = BloomFilterHeader::read_from_in_protocol(&mut protocol).ok()?;
+                Some((header, bytes.len() - input.len()))
+            }) else {
+                return;
+            };
+            let size = header_size as u64 + header.num_bytes.max(0) as u64;
+            if header.num_bytes <= 0 || size > remaining {
+                return;
+            }
+            self.offset += size;
+        }
+    }
+
+    /// Decode a header at `offset`, first from a small window of bytes, then
+    /// from a larger one in case the header is long.
+    fn read_header<T>(
+        &self,
+        offset: u64,
+        remaining: u64,
+        parse: impl Fn(&[u8]) -> Option<(T, usize)>,
+    ) -> Option<(T, usize)> {
+        let mut window = remaining.min(HEADER_WINDOW);
+        loop {
+            let bytes = self.reader.get_bytes(offset, window as usize).ok()?;
+            match parse(&bytes) {
+                Some(header) => return Some(header),
+                None if window < remaining.min(MAX_HEADER_SIZE) => {
+                    window = remaining.min(MAX_HEADER_SIZE)
+                }
+                None => return None,
+            }
+        }
+    }
+}
+
+fn chunk_metadata(
+    leaf: ColumnDescPtr,
+    pages: &[ScannedPage],
+    codec: Compression,
+) -> parquet::errors::Result<ColumnChunkMetaData> {
+    let dictionary = pages.first().filter(|page| page.is_dictionary());
+    let data_page_offset = pages
+        .iter()
+        .find(|page| !page.is_dictionary())
+        .map_or(0, |page| page.offset);
+    let mut encodings = Vec::new();
+    for encoding in pages.iter().filter_map(ScannedPage::encoding) {
+        if !encodings.contains(&encoding) {
+            encodings.push(encoding);
+        }
+    }
+    let uncompressed_size = pages
+        .iter()
+        .map(|page| {
+            page.size as i64 - page.header.compressed_page_size as i64
+                + page.header.uncompressed_page_size as i64
+        })
+        .sum();
+
+    ColumnChunkMetaData::builder(leaf)
+        .set_compression(codec)
+        .set_encodings(encodings)
+        .set_num_values(pages.iter().map(ScannedPage::num_values).sum())
+        .set_total_compressed_size(pages.iter().map(|page| page.size as i64).sum())
+        .set_total_uncompressed_size(uncompressed_size)
+        .set_data_page_offset(data_page_offset as i64)
+        .set_dictionary_page_offset(dictionary.map(|page| page.offset as i64))
+        .build()
+}
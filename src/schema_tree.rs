@@ -0,0 +1,220 @@
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::PARQUET_FIELD_ID_META_KEY;
+use parquet::basic::{ConvertedType, LogicalType, Repetition, TimeUnit};
+use parquet::schema::types::Type;
+
+/// A field of a schema with its nested fields, see
+/// [`ParquetViewerFile::schema_tree`](crate::ParquetViewerFile::schema_tree).
+///
+/// Parquet details are only present for Parquet files.
+#[derive(Debug, Clone)]
+pub struct SchemaNode {
+    pub name: String,
+    /// `REQUIRED`, `OPTIONAL` or `REPEATED`, `None` for the root.
+    pub repetition: Option<String>,
+    /// Parquet physical type of leaves, e.g. `FIXED_LEN_BYTE_ARRAY(16)`.
+    pub physical_type: Option<String>,
+    /// Parquet logical type, e.g. `TIMESTAMP(MICROS,true)`.
+    pub logical_type: Option<String>,
+    /// Legacy Parquet converted type, e.g. `UTF8`.
+    pub converted_type: Option<String>,
+    pub field_id: Option<i32>,
+    /// Arrow type the field is read as, nested types without their fields,
+    /// e.g. `List`.
+    pub arrow_type: Option<String>,
+    /// Metadata of the Arrow field, without the field ID.
+    pub metadata: Vec<(String, String)>,
+    pub children: Vec<SchemaNode>,
+}
+
+impl SchemaNode {
+    /// Tree of an Arrow schema, for files without a Parquet schema.
+    pub fn from_arrow(schema: &Schema) -> Self {
+        Self {
+            children: schema.fields().iter().map(|f| arrow_node(f)).collect(),
+            ..Self::new("schema")
+        }
+    }
+
+    /// Tree of a Parquet schema, annotated with the Arrow fields it is read
+    /// as.
+    pub(crate) fn from_parquet(root: &Type, schema: &Schema) -> Self {
+        let children = root
+            .get_fields()
+            .iter()
+            .map(|child| {
+                let field = schema.fields().iter().find(|f| f.name() == child.name());
+                parquet_node(child, field.map(|f| f.as_ref()), None)
+            })
+            .collect();
+        Self {
+            children,
+            ..Self::new(root.name())
+        }
+    }
+
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            repetition: None,
+            physical_type: None,
+            logical_type: None,
+            converted_type: None,
+            field_id: None,
+            arrow_type: None,
+            metadata: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+}
+
+fn arrow_node(field: &Field) -> SchemaNode {
+    let children = match field.data_type() {
+        DataType::Struct(fields) => fields.iter().map(|f| arrow_node(f)).collect(),
+        DataType::List(item)
+        | DataType::LargeList(item)
+        | DataType::ListView(item)
+        | DataType::LargeListView(item)
+        | DataType::FixedSizeList(item, _)
+        | DataType::Map(item, _) => vec![arrow_node(item)],
+        _ => Vec::new(),
+    };
+    let repetition = if field.is_nullable() {
+        "OPTIONAL"
+    } else {
+        "REQUIRED"
+    };
+
+    SchemaNode {
+        repetition: Some(repetition.to_string()),
+        field_id: field
+            .metadata()
+            .get(PARQUET_FIELD_ID_META_KEY)
+            .and_then(|id| id.parse().ok()),
+        children,
+        ..annotate(SchemaNode::new(field.name()), Some(field))
+    }
+}
+
+/// Node of a Parquet type and the Arrow field it is read as, if any.
+///
+/// `items` is the item field of an Arrow list whose elements are wrapped in
+/// the repeated group `ty`, which has no Arrow field of its own.
+fn parquet_node(ty: &Type, field: Option<&Field>, items: Option<&Field>) -> SchemaNode {
+    let info = ty.get_basic_info();
+    let fields = if ty.is_group() { ty.get_fields() } else { &[] };
+
+    let children = fields
+        .iter()
+        .map(|child| {
+            if items.is_some() {
+                return parquet_node(child, items, None);
+            }
+            match field.map(|f| f.data_type()) {
+                Some(DataType::Struct(fields)) => {
+                    let field = fields.iter().find(|f| f.name() == child.name());
+                    parquet_node(child, field.map(|f| f.as_ref()), None)
+                }
+                Some(DataType::Map(entries, _)) => parquet_node(child, Some(entries), None),
+                Some(
+                    DataType::List(item)
+                    | DataType::LargeList(item)
+                    | DataType::FixedSizeList(item, _),
+                ) if is_list_wrapper(ty, child) => parquet_node(child, None, Some(item)),
+                Some(
+                    DataType::List(item)
+                    | DataType::LargeList(item)
+                    | DataType::FixedSizeList(item, _),
+                ) => parquet_node(child, Some(item), None),
+                _ => parquet_node(child, None, None),
+            }
+        })
+        .collect();
+
+    let physical_type = match ty {
+        Type::PrimitiveType {
+            physical_type,
+            type_length,
+            ..
+        } if *type_length > 0 => Some(format!("{physical_type}({type_length})")),
+        Type::PrimitiveType { physical_type, .. } => Some(physical_type.to_string()),
+        Type::GroupType { .. } => None,
+    };
+    let repetition = info.has_repetition().then(|| match info.repetition() {
+        Repetition::REQUIRED => "REQUIRED",
+        Repetition::OPTIONAL => "OPTIONAL",
+        Repetition::REPEATED => "REPEATED",
+    });
+
+    let node = SchemaNode {
+        repetition: repetition.map(str::to_string),
+        physical_type,
+        logical_type: info.logical_type().map(|t| format_logical_type(&t)),
+        converted_type: (info.converted_type() != ConvertedType::NONE)
+            .then(|| info.converted_type().to_string()),
+        field_id: info.has_id().then(|| info.id()),
+        children,
+        ..SchemaNode::new(ty.name())
+    };
+    annotate(node, field)
+}
+
+/// Add the Arrow type and metadata of `field` to a node.
+fn annotate(mut node: SchemaNode, field: Option<&Field>) -> SchemaNode {
+    let Some(field) = field else {
+        return node;
+    };
+    node.arrow_type = Some(match field.data_type() {
+        DataType::Struct(_) => "Struct".to_string(),
+        DataType::List(_) => "List".to_string(),
+        DataType::LargeList(_) => "LargeList".to_string(),
+        DataType::ListView(_) => "ListView".to_string(),
+        DataType::LargeListView(_) => "LargeListView".to_string(),
+        DataType::FixedSizeList(_, size) => format!("FixedSizeList({size})"),
+        DataType::Map(_, _) => "Map".to_string(),
+        data_type => data_type.to_string(),
+    });
+    node.metadata = field
+        .metadata()
+        .iter()
+        .filter(|(key, _)| key.as_str() != PARQUET_FIELD_ID_META_KEY)
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    node.metadata.sort();
+    node
+}
+
+/// Whether `child` is the repeated group wrapping the element of a three
+/// level Parquet list, rather than the element of a legacy two level list.
+fn is_list_wrapper(list: &Type, child: &Type) -> bool {
+    child.is_group()
+        && child.get_basic_info().repetition() == Repetition::REPEATED
+        && child.get_fields().len() == 1
+        && child.name() != "array"
+        && child.name() != format!("{}_tuple", list.name())
+}
+
+/// Render a logical type the way Parquet tools print schemas.
+fn format_logical_type(logical_type: &LogicalType) -> String {
+    let unit = |unit: &TimeUnit| match unit {
+        TimeUnit::MILLIS(_) => "MILLIS",
+        TimeUnit::MICROS(_) => "MICROS",
+        TimeUnit::NANOS(_) => "NANOS",
+    };
+    match logical_type {
+        LogicalType::Decimal { scale, precision } => format!("DECIMAL({precision},{scale})"),
+        LogicalType::Integer {
+            bit_width,
+            is_signed,
+        } => format!("INTEGER({bit_width},{is_signed})"),
+        LogicalType::Time {
+            is_adjusted_to_u_t_c,
+            unit: time_unit,
+        } => format!("TIME({},{is_adjusted_to_u_t_c})", unit(time_unit)),
+        LogicalType::Timestamp {
+            is_adjusted_to_u_t_c,
+            unit: time_unit,
+        } => format!("TIMESTAMP({},{is_adjusted_to_u_t_c})", unit(time_unit)),
+        other => format!("{other:?}").to_uppercase(),
+    }
+}
@@ -0,0 +1,342 @@
+use crate::format_bytes;
+use arrow::datatypes::{ArrowPrimitiveType, Float16Type};
+use parquet::basic::{LogicalType, SortOrder};
+use parquet::data_type::FixedLenByteArray;
+use parquet::file::metadata::ParquetMetaData;
+use parquet::file::statistics::Statistics;
+use parquet::schema::types::ColumnDescriptor;
+use std::cmp::Ordering;
+use std::fmt;
+
+type F16 = <Float16Type as ArrowPrimitiveType>::Native;
+
+/// File-level statistics of a Parquet leaf column, aggregated from the
+/// column chunk statistics of every row group without decoding any data.
+///
+/// Min and max are rendered from the physical values, like
+/// [`ChunkStatistics`](crate::ChunkStatistics).
+#[derive(Debug, Clone)]
+pub struct ColumnStatisticsSummary {
+    /// Dotted path of the leaf column.
+    pub path: String,
+    pub physical_type: String,
+    pub min: Option<String>,
+    pub max: Option<String>,
+    /// Whether min and max are the actual values of the column, rather than
+    /// bounds from truncated or missing row group statistics.
+    pub is_min_exact: bool,
+    pub is_max_exact: bool,
+    /// Total nulls, if every row group records its null count.
+    pub null_count: Option<u64>,
+    /// Largest distinct count of any row group, if every row group records
+    /// one. With several row groups this is a lower bound for the file.
+    pub distinct_count: Option<u64>,
+    pub num_row_groups: usize,
+    /// Reasons the statistics above may be incomplete or imprecise.
+    pub issues: Vec<StatisticsIssue>,
+}
+
+/// A problem with the stored statistics of a column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatisticsIssue {
+    /// Row groups without statistics for the column.
+    Missing { row_groups: usize },
+    /// Row groups with non-null values but without min or max.
+    MissingMinMax { row_groups: usize },
+    /// Row groups without a null count.
+    MissingNullCount { row_groups: usize },
+    /// Row groups whose min or max cannot be decoded, such as a Float16
+    /// value of less than two bytes.
+    InvalidMinMax { row_groups: usize },
+    /// Row groups whose min or max was truncated by the writer.
+    Inexact { row_groups: usize },
+    /// The column type has no defined sort order, so min and max cannot be
+    /// compared across row groups.
+    UndefinedSortOrder,
+}
+
+impl fmt::Display for StatisticsIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { row_groups } => write!(f, "no statistics in {row_groups} row groups"),
+            Self::MissingMinMax { row_groups } => {
+                write!(f, "no min/max in {row_groups} row groups")
+            }
+            Self::MissingNullCount { row_groups } => {
+                write!(f, "no null count in {row_groups} row groups")
+            }
+            Self::InvalidMinMax { row_groups } => {
+                write!(f, "invalid min/max in {row_groups} row groups")
+            }
+            Self::Inexact { row_groups } => write!(f, "inexact min/max in {row_groups} row groups"),
+            Self::UndefinedSortOrder => write!(f, "min/max have no defined sort order"),
+        }
+    }
+}
+
+/// A min or max value, compared according to the sort order of its column.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Bound {
+    Boolean(bool),
+    Signed(i64),
+    Unsigned(u64),
+    Float(f64),
+    /// Byte arrays compared byte-wise, such as strings.
+    Bytes(Vec<u8>),
+    /// Big-endian two's complement integers, such as decimals.
+    SignedBytes(Vec<u8>),
+}
+
+impl Bound {
+    pub(crate) fn compare(&self, other: &Bound) -> Ordering {
+        match (self, other) {
+            (Self::Boolean(a), Self::Boolean(b)) => a.cmp(b),
+            (Self::Signed(a), Self::Signed(b)) => a.cmp(b),
+            (Self::Unsigned(a), Self::Unsigned(b)) => a.cmp(b),
+            (Self::Float(a), Self::Float(b)) => a.total_cmp(b),
+            (Self::Bytes(a), Self::Bytes(b)) => a.cmp(b),
+            (Self::SignedBytes(a), Self::SignedBytes(b)) => compare_signed_bytes(a, b),
+            // A column always produces the same kind of bound
+            _ => Ordering::Equal,
+        }
+    }
+
+    pub(crate) fn render(&self) -> String {
+        match self {
+            Self::Boolean(v) => v.to_string(),
+            Self::Signed(v) => v.to_string(),
+            Self::Unsigned(v) => v.to_string(),
+            Self::Float(v) => v.to_string(),
+            Self::Bytes(v) | Self::SignedBytes(v) => format_bytes(v),
+        }
+    }
+}
+
+/// Compare big-endian two's complement integers of possibly different widths.
+fn compare_signed_bytes(a: &[u8], b: &[u8]) -> Ordering {
+    let is_negative = |v: &[u8]| v.first().is_some_and(|byte| byte & 0x80 != 0);
+    match (is_negative(a), is_negative(b)) {
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (negative, _) => {
+            // Sign extend the shorter value
+            let pad = if negative { 0xff } else { 0 };
+            let width = a.len().max(b.len());
+            let extend = |v: &[u8]| {
+                std::iter::repeat_n(pad, width - v.len())
+                    .chain(v.iter().copied())
+                    .collect::<Vec<u8>>()
+            };
+            extend(a).cmp(&extend(b))
+        }
+    }
+}
+
+/// Aggregate the statistics of every leaf column across all row groups.
+pub(crate) fn summarize_statistics(metadata: &ParquetMetaData) -> Vec<ColumnStatisticsSummary> {
+    let descr = metadata.file_metadata().schema_descr();
+    (0..descr.num_columns())
+        .map(|i| summarize_column(metadata, &descr.column(i), i))
+        .collect()
+}
+
+fn summarize_column(
+    metadata: &ParquetMetaData,
+    column: &ColumnDescriptor,
+    index: usize,
+) -> ColumnStatisticsSummary {
+    let sort_order = column.sort_order();
+    let is_float16 = matches!(column.logical_type(), Some(LogicalType::Float16));
+
+    let mut min: Option<(Bound, bool)> = None;
+    let mut max: Option<(Bound, bool)> = None;
+    let mut null_count = Some(0);
+    let mut distinct_count = Some(0);
+    let mut missing = 0;
+    let mut missing_min_max = 0;
+    let mut missing_null_count = 0;
+    let mut invalid_min_max = 0;
+    let mut inexact = 0;
+
+    for row_group in metadata.row_groups() {
+        let chunk = row_group.column(index);
+        let Some(statistics) = chunk.statistics() else {
+            missing += 1;
+            null_count = None;
+            distinct_count = None;
+            continue;
+        };
+
+        match statistics.null_count_opt() {
+            Some(nulls) => null_count = null_count.map(|total| total + nulls),
+            None => {
+                missing_null_count += 1;
+                null_count = None;
+            }
+        }
+        distinct_count = distinct_count
+            .zip(statistics.distinct_count_opt())
+            .map(|(a, b)| a.max(b));
+
+        let (chunk_min, chunk_max) = bounds(statistics, sort_order, is_float16);
+        // Chunks of only nulls legitimately have no min or max
+        let all_nulls = statistics
+            .null_count_opt()
+            .is_some_and(|nulls| nulls as i64 >= chunk.num_values());
+        let invalid = sort_order != SortOrder::UNDEFINED
+            && (statistics.min_bytes_opt().is_some() && chunk_min.is_none()
+                || statistics.max_bytes_opt().is_some() && chunk_max.is_none());
+        if invalid {
+            invalid_min_max += 1;
+        } else if (chunk_min.is_none() || chunk_max.is_none()) && !all_nulls {
+            missing_min_max += 1;
+        }
+        if !statistics.min_is_exact() && chunk_min.is_some()
+            || !statistics.max_is_exact() && chunk_max.is_some()
+        {
+            inexact += 1;
+        }
+
+        if let Some(value) = chunk_min {
+            min = Some(merge(min, value, statistics.min_is_exact(), Ordering::Less));
+        }
+        if let Some(value) = chunk_max {
+            max = Some(merge(
+                max,
+                value,
+                statistics.max_is_exact(),
+                Ordering::Greater,
+            ));
+        }
+    }
+
+    let mut issues = Vec::new();
+    if missing > 0 {
+        issues.push(StatisticsIssue::Missing {
+            row_groups: missing,
+        });
+    }
+    if sort_order == SortOrder::UNDEFINED {
+        issues.push(StatisticsIssue::UndefinedSortOrder);
+    } else if missing_min_max > 0 {
+        issues.push(StatisticsIssue::MissingMinMax {
+            row_groups: missing_min_max,
+        });
+    }
+    if missing_null_count > 0 {
+        issues.push(StatisticsIssue::MissingNullCount {
+            row_groups: missing_null_count,
+        });
+    }
+    if invalid_min_max > 0 {
+        issues.push(StatisticsIssue::InvalidMinMax {
+            row_groups: invalid_min_max,
+        });
+    }
+    if inexact > 0 {
+        issues.push(StatisticsIssue::Inexact {
+            row_groups: inexact,
+        });
+    }
+
+    // Row groups without min or max may hold values beyond the bounds found
+    let complete = missing == 0 && missing_min_max == 0 && invalid_min_max == 0;
+    let (min, is_min_exact) = split(min, complete);
+    let (max, is_max_exact) = split(max, complete);
+
+    ColumnStatisticsSummary {
+        path: column.path().string(),
+        physical_type: column.physical_type().to_string(),
+        min,
+        max,
+        is_min_exact,
+        is_max_exact,
+        null_count,
+        distinct_count: distinct_count.filter(|_| !metadata.row_groups().is_empty()),
+        num_row_groups: metadata.num_row_groups(),
+        issues,
+    }
+}
+
+/// Keep the lower (`Ordering::Less`) or higher bound, preferring an exact
+/// value over an equal inexact one.
+fn merge(
+    current: Option<(Bound, bool)>,
+    value: Bound,
+    exact: bool,
+    keep: Ordering,
+) -> (Bound, bool) {
+    match current {
+        Some((current, current_exact)) => match value.compare(&current) {
+            ordering if ordering == keep => (value, exact),
+            Ordering::Equal => (current, current_exact || exact),
+            _ => (current, current_exact),
+        },
+        None => (value, exact),
+    }
+}
+
+fn split(bound: Option<(Bound, bool)>, complete: bool) -> (Option<String>, bool) {
+    match bound {
+        Some((bound, exact)) => (Some(bound.render()), exact && complete),
+        None => (None, false),
+    }
+}
+
+/// Min and max of a column chunk, or `None` when they cannot be compared.
+pub(crate) fn bounds(
+    statistics: &Statistics,
+    sort_order: SortOrder,
+    is_float16: bool,
+) -> (Option<Bound>, Option<Bound>) {
+    fn pair<T>(
+        min: Option<&T>,
+        max: Option<&T>,
+        bound: impl Fn(&T) -> Bound,
+    ) -> (Option<Bound>, Option<Bound>) {
+        (min.map(&bound), max.map(&bound))
+    }
+
+    let unsigned = sort_order == SortOrder::UNSIGNED;
+    match statistics {
+        _ if sort_order == SortOrder::UNDEFINED => (None, None),
+        Statistics::Boolean(s) => pair(s.min_opt(), s.max_opt(), |v| Bound::Boolean(*v)),
+        Statistics::Int32(s) if unsigned => pair(s.min_opt(), s.max_opt(), |v| {
+            Bound::Unsigned(*v as u32 as u64)
+        }),
+        Statistics::Int32(s) => pair(s.min_opt(), s.max_opt(), |v| Bound::Signed(*v as i64)),
+        Statistics::Int64(s) if unsigned => {
+            pair(s.min_opt(), s.max_opt(), |v| Bound::Unsigned(*v as u64))
+        }
+        Statistics::Int64(s) => pair(s.min_opt(), s.max_opt(), |v| Bound::Signed(*v)),
+        Statistics::Float(s) => pair(s.min_opt(), s.max_opt(), |v| Bound::Float(*v as f64)),
+        Statistics::Double(s) => pair(s.min_opt(), s.max_opt(), |v| Bound::Float(*v)),
+        Statistics::ByteArray(s) if unsigned => pair(s.min_opt(), s.max_opt(), |v| {
+            Bound::Bytes(v.data().to_vec())
+        }),
+        // Decimals
+        Statistics::ByteArray(s) => pair(s.min_opt(), s.max_opt(), |v| {
+            Bound::SignedBytes(v.data().to_vec())
+        }),
+        // A value of less than two bytes is invalid and has no bound
+        Statistics::FixedLenByteArray(s) if is_float16 => {
+            let bound = |v: &FixedLenByteArray| float16(v).map(Bound::Float);
+            (s.min_opt().and_then(bound), s.max_opt().and_then(bound))
+        }
+        Statistics::FixedLenByteArray(s) if unsigned => pair(s.min_opt(), s.max_opt(), |v| {
+            Bound::Bytes(v.data().to_vec())
+        }),
+        Statistics::FixedLenByteArray(s) => pair(s.min_opt(), s.max_opt(), |v| {
+            Bound::SignedBytes(v.data().to_vec())
+        }),
+        // INT96 timestamps have an undefined sort order
+        Statistics::Int96(_) => (None, None),
+    }
+}
+
+/// Decode a FLOAT16 value, stored as two little-endian bytes, or `None` if
+/// it has less than two bytes.
+pub(crate) fn float16(value: &FixedLenByteArray) -> Option<f64> {
+    let bytes = value.data().get(..2)?.try_into().ok()?;
+    Some(F16::from_le_bytes(bytes).to_f64())
+}
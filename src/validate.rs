@@ -0,0 +1,400 @@
+use crate::inspect::{RawPage, chunk_range, read_page_header};
+use crate::{BoundedReader, FileFormat, ParquetViewerError, Result, format_from_magic};
+use parquet::column::reader::{ColumnReader, ColumnReaderImpl};
+use parquet::data_type::DataType as ParquetType;
+use parquet::file::metadata::{ParquetMetaData, ParquetMetaDataReader};
+use parquet::file::properties::ReaderProperties;
+use parquet::file::reader::{ChunkReader, Length, RowGroupReader};
+use parquet::file::serialized_reader::SerializedRowGroupReader;
+use std::fmt;
+use std::sync::Arc;
+
+/// Magic bytes at the start and end of every Parquet file.
+const PARQUET_MAGIC: &[u8; 4] = b"PAR1";
+
+/// Length and magic bytes that close a Parquet file.
+const FOOTER_TRAILER_SIZE: u64 = 8;
+
+/// Number of records decoded at once when checking row counts.
+const BATCH_SIZE: usize = 8192;
+
+/// Everything found wrong with a Parquet file, see
+/// [`validate_file`](crate::validate_file).
+#[derive(Debug, Clone)]
+pub struct ValidationReport {
+    pub file_size: u64,
+    /// Row groups listed in the footer, zero when it cannot be read.
+    pub num_row_groups: usize,
+    /// Column chunks whose pages were all read.
+    pub column_chunks_checked: usize,
+    pub pages_checked: usize,
+    /// Pages with a checksum, which was verified.
+    pub pages_with_crc: usize,
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// A problem found while validating a Parquet file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationIssue {
+    /// The file is too small to hold the magic bytes and a footer.
+    TooSmall { size: u64 },
+    /// The file does not start with `PAR1`.
+    BadHeaderMagic { found: Vec<u8> },
+    /// The file does not end with `PAR1`, usually because it was truncated.
+    BadFooterMagic { found: Vec<u8> },
+    /// The footer length points before the start of the file.
+    BadFooterLength { length: u64, file_size: u64 },
+    /// The footer could not be decoded.
+    CorruptFooter { error: String },
+    /// A column chunk lies outside the data between the leading magic bytes
+    /// and the footer, or has a negative offset or length.
+    ChunkOutOfBounds {
+        row_group: usize,
+        column: String,
+        offset: i64,
+        length: i64,
+    },
+    /// A page header could not be read, so the rest of its column chunk
+    /// was not checked.
+    CorruptPageHeader {
+        row_group: usize,
+        column: String,
+        offset: u64,
+        error: String,
+    },
+    /// The checksum of a page does not match its data.
+    CrcMismatch {
+        row_group: usize,
+        column: String,
+        offset: u64,
+        expected: u32,
+        actual: u32,
+    },
+    /// A page of the column chunk failed to decompress or decode.
+    DecodeError {
+        row_group: usize,
+        column: String,
+        error: String,
+    },
+    /// The column chunk decodes to a different number of rows than the row
+    /// group holds.
+    RowCountMismatch {
+        row_group: usize,
+        column: String,
+        expected: i64,
+        actual: i64,
+    },
+    /// The column chunk decodes to a different number of values than
+    /// recorded in its metadata.
+    ValueCountMismatch {
+        row_group: usize,
+        column: String,
+        expected: i64,
+        actual: i64,
+    },
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hex = |bytes: &[u8]| bytes.iter().map(|b| format!("{b:02x}")).collect::<String>();
+        match self {
+            Self::TooSmall { size } => write!(f, "file of {size} bytes is too small"),
+            Self::BadHeaderMagic { found } => {
+                write!(f, "file starts with {} instead of PAR1", hex(found))
+            }
+            Self::BadFooterMagic { found } => {
+                write!(f, "file ends with {} instead of PAR1", hex(found))
+            }
+            Self::BadFooterLength { length, file_size } => write!(
+                f,
+                "footer of {length} bytes does not fit in a file of {file_size} bytes"
+            ),
+            Self::CorruptFooter { error } => write!(f, "corrupt footer: {error}"),
+            Self::ChunkOutOfBounds {
+                row_group,
+                column,
+                offset,
+                length,
+            } => write!(
+                f,
+                "row group {row_group}, column {column}: chunk of {length} bytes at offset {offset} lies outside the data"
+            ),
+            Self::CorruptPageHeader {
+                row_group,
+                column,
+                offset,
+                error,
+            } => write!(
+                f,
+                "row group {row_group}, column {column}: corrupt page header at offset {offset}: {error}"
+            ),
+            Self::CrcMismatch {
+                row_group,
+                column,
+                offset,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "row group {row_group}, column {column}: page at offset {offset} has CRC {actual:08x}, expected {expected:08x}"
+            ),
+            Self::DecodeError {
+                row_group,
+                column,
+                error,
+            } => write!(f, "row group {row_group}, column {column}: {error}"),
+            Self::RowCountMismatch {
+                row_group,
+                column,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "row group {row_group}, column {column}: decoded {actual} rows, expected {expected}"
+            ),
+            Self::ValueCountMismatch {
+                row_group,
+                column,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "row group {row_group}, column {column}: decoded {actual} values, expected {expected}"
+            ),
+        }
+    }
+}
+
+/// Check the magic bytes, footer and every page of a Parquet file.
+///
+/// Problems with the file are reported rather than returned as errors, which
+/// are kept for failures to read it at all.
+pub(crate) fn validate<R: ChunkReader + 'static>(reader: R) -> Result<ValidationReport> {
+    let reader = BoundedReader(reader);
+    let file_size = reader.len();
+    let mut report = ValidationReport {
+        file_size,
+        num_row_groups: 0,
+        column_chunks_checked: 0,
+        pages_checked: 0,
+        pages_with_crc: 0,
+        issues: Vec::new(),
+    };
+
+    let magic_size = PARQUET_MAGIC.len() as u64;
+    if file_size < magic_size + FOOTER_TRAILER_SIZE {
+        report
+            .issues
+            .push(ValidationIssue::TooSmall { size: file_size });
+        return Ok(report);
+    }
+
+    let header = reader.get_bytes(0, PARQUET_MAGIC.len())?;
+    match format_from_magic(&header) {
+        Some(FileFormat::Arrow | FileFormat::ArrowStream) => {
+            return Err(ParquetViewerError::ParquetRequired(
+                "Validation".to_string(),
+            ));
+        }
+        Some(FileFormat::Parquet) => {}
+        None => report.issues.push(ValidationIssue::BadHeaderMagic {
+            found: header.to_vec(),
+        }),
+    }
+
+    let trailer = reader.get_bytes(
+        file_size - FOOTER_TRAILER_SIZE,
+        FOOTER_TRAILER_SIZE as usize,
+    )?;
+    if &trailer[4..] != PARQUET_MAGIC {
+        report.issues.push(ValidationIssue::BadFooterMagic {
+            found: trailer[4..].to_vec(),
+        });
+        return Ok(report);
+    }
+    let footer_length = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]) as u64;
+    if footer_length + FOOTER_TRAILER_SIZE + magic_size > file_size {
+        report.issues.push(ValidationIssue::BadFooterLength {
+            length: footer_length,
+            file_size,
+        });
+        return Ok(report);
+    }
+
+    let metadata = match ParquetMetaDataReader::new().parse_and_finish(&reader) {
+        Ok(metadata) => metadata,
+        Err(error) => {
+            report.issues.push(ValidationIssue::CorruptFooter {
+                error: error.to_string(),
+            });
+            return Ok(report);
+        }
+    };
+    report.num_row_groups = metadata.num_row_groups();
+
+    // Column chunks must lie between the leading magic bytes and the footer
+    let data_end = file_size - footer_length - FOOTER_TRAILER_SIZE;
+    let reader = Arc::new(reader);
+    for row_group in 0..metadata.num_row_groups() {
+        for column in 0..metadata.row_group(row_group).num_columns() {
+            validate_column_chunk(&reader, &metadata, row_group, column, data_end, &mut report)?;
+        }
+    }
+    Ok(report)
+}
+
+fn validate_column_chunk<R: ChunkReader + 'static>(
+    reader: &Arc<R>,
+    metadata: &ParquetMetaData,
+    row_group: usize,
+    column: usize,
+    data_end: u64,
+    report: &mut ValidationReport,
+) -> Result<()> {
+    let row_group_metadata = metadata.row_group(row_group);
+    let chunk = row_group_metadata.column(column);
+    let path = chunk.column_path().string();
+    let Some(range) = chunk_range(chunk)
+        .filter(|range| range.start >= PARQUET_MAGIC.len() as u64 && range.end <= data_end)
+    else {
+        report.issues.push(ValidationIssue::ChunkOutOfBounds {
+            row_group,
+            column: path,
+            offset: chunk
+                .dictionary_page_offset()
+                .unwrap_or(chunk.data_page_offset()),
+            length: chunk.compressed_size(),
+        });
+        return Ok(());
+    };
+
+    // Walk the page headers, verifying checksums
+    let start = range.start;
+    let bytes = reader.get_bytes(start, (range.end - start) as usize)?;
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let page = match read_page_header(&bytes, offset) {
+            Ok(page) => page,
+            Err(error) => {
+                report.issues.push(ValidationIssue::CorruptPageHeader {
+                    row_group,
+                    column: path,
+                    offset: start + offset as u64,
+                    error: error.to_string(),
+                });
+                return Ok(());
+            }
+        };
+        check_crc(&bytes, &page, start, row_group, &path, report);
+        report.pages_checked += 1;
+        offset = page.data.end;
+    }
+
+    // Decode every page, counting rows and values
+    let props = Arc::new(ReaderProperties::builder().build());
+    let counts = SerializedRowGroupReader::new(reader.clone(), row_group_metadata, None, props)
+        .and_then(|row_group| row_group.get_column_reader(column))
+        .map_err(ParquetViewerError::from)
+        .and_then(count_records);
+    let (rows, values) = match counts {
+        Ok(counts) => counts,
+        Err(error) => {
+            report.issues.push(ValidationIssue::DecodeError {
+                row_group,
+                column: path,
+                error: error.to_string(),
+            });
+            return Ok(());
+        }
+    };
+    if rows != row_group_metadata.num_rows() {
+        report.issues.push(ValidationIssue::RowCountMismatch {
+            row_group,
+            column: path.clone(),
+            expected: row_group_metadata.num_rows(),
+            actual: rows,
+        });
+    }
+    if values != chunk.num_values() {
+        report.issues.push(ValidationIssue::ValueCountMismatch {
+            row_group,
+            column: path,
+            expected: chunk.num_values(),
+            actual: values,
+        });
+    }
+    report.column_chunks_checked += 1;
+    Ok(())
+}
+
+fn check_crc(
+    bytes: &[u8],
+    page: &RawPage,
+    chunk_offset: u64,
+    row_group: usize,
+    column: &str,
+    report: &mut ValidationReport,
+) {
+    let Some(expected) = page.header.crc else {
+        return;
+    };
+    report.pages_with_crc += 1;
+    let expected = expected as u32;
+    let actual = crc32fast::hash(&bytes[page.data.clone()]);
+    if actual != expected {
+        report.issues.push(ValidationIssue::CrcMismatch {
+            row_group,
+            column: column.to_string(),
+            offset: chunk_offset + page.offset as u64,
+            expected,
+            actual,
+        });
+    }
+}
+
+/// Decode a whole column chunk, returning its number of rows and of values,
+/// including nulls.
+pub(crate) fn count_records(reader: ColumnReader) -> Result<(i64, i64)> {
+    match reader {
+        ColumnReader::BoolColumnReader(r) => count_typed_records(r),
+        ColumnReader::Int32ColumnReader(r) => count_typed_records(r),
+        ColumnReader::Int64ColumnReader(r) => count_typed_records(r),
+        ColumnReader::Int96ColumnReader(r) => count_typed_records(r),
+        ColumnReader::FloatColumnReader(r) => count_typed_records(r),
+        ColumnReader::DoubleColumnReader(r) => count_typed_records(r),
+        ColumnReader::ByteArrayColumnReader(r) => count_typed_records(r),
+        ColumnReader::FixedLenByteArrayColumnReader(r) => count_typed_records(r),
+    }
+}
+
+fn count_typed_records<T: ParquetType>(mut reader: ColumnReaderImpl<T>) -> Result<(i64, i64)> {
+    let mut def_levels = Vec::new();
+    let mut rep_levels = Vec::new();
+    let mut values = Vec::new();
+    let mut rows = 0;
+    let mut num_values = 0;
+    loop {
+        def_levels.clear();
+        rep_levels.clear();
+        values.clear();
+        let (records, values_read, levels_read) = reader.read_records(
+            BATCH_SIZE,
+            Some(&mut def_levels),
+            Some(&mut rep_levels),
+            &mut values,
+        )?;
+        if records == 0 && levels_read == 0 {
+            break;
+        }
+        rows += records as i64;
+        // Levels are not stored for required columns outside of lists
+        num_values += levels_read.max(values_read) as i64;
+    }
+    Ok((rows, num_values))
+}
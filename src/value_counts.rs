@@ -0,0 +1,170 @@
+use crate::profile::column_array;
+use crate::{BatchReader, Result};
+use arrow::array::{Array, AsArray};
+use arrow::datatypes::DataType;
+use arrow::row::{RowConverter, SortField};
+use arrow::util::display::array_value_to_string;
+use std::collections::HashMap;
+
+/// Number of distinct values counted exactly. Beyond twice this many, only
+/// the most frequent values are tracked.
+const EXACT_LIMIT: usize = 100_000;
+
+/// Most frequent values of a column, see
+/// [`ParquetViewerFile::top_values`](crate::ParquetViewerFile::top_values).
+#[derive(Debug, Clone)]
+pub struct ValueCounts {
+    /// Column name, or dotted path of a field of a struct column.
+    pub column: String,
+    /// Most frequent non-null values, most frequent first.
+    pub values: Vec<ValueCount>,
+    pub row_count: u64,
+    pub null_count: u64,
+    /// How much any count may be below the actual count, once the column has
+    /// too many distinct values to count them all exactly.
+    pub max_error: u64,
+}
+
+impl ValueCounts {
+    pub fn is_exact(&self) -> bool {
+        self.max_error == 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueCount {
+    pub value: String,
+    pub count: u64,
+}
+
+/// Count the values of the single column read by `reader`, `depth` levels
+/// down struct columns, and keep the `k` most frequent.
+///
+/// Dictionary encoded columns are counted by key, so each distinct value is
+/// only encoded once per dictionary.
+pub(crate) fn count_values(
+    reader: BatchReader,
+    column: &str,
+    depth: usize,
+    k: usize,
+) -> Result<ValueCounts> {
+    let mut data_type = reader.schema().field(0).data_type().clone();
+    for _ in 1..depth {
+        let DataType::Struct(fields) = data_type else {
+            unreachable!("projected struct fields keep their path");
+        };
+        data_type = fields[0].data_type().clone();
+    }
+    let value_type = match &data_type {
+        DataType::Dictionary(_, value_type) => value_type.as_ref().clone(),
+        data_type => data_type.clone(),
+    };
+    let converter = RowConverter::new(vec![SortField::new(value_type)])?;
+
+    let mut counter = HeavyHitters::new(EXACT_LIMIT.max(k));
+    let mut row_count = 0;
+    let mut null_count = 0;
+    for batch in reader {
+        let array = column_array(&batch?, &vec![0; depth])?;
+        let nulls = array.logical_nulls();
+        row_count += array.len() as u64;
+        null_count += nulls.as_ref().map_or(0, |nulls| nulls.null_count()) as u64;
+        let is_valid = |i: usize| nulls.as_ref().is_none_or(|nulls| nulls.is_valid(i));
+
+        if let Some(dictionary) = array.as_any_dictionary_opt() {
+            // Batches of only nulls may come with an empty dictionary
+            if dictionary.values().is_empty() {
+                continue;
+            }
+            let mut key_counts = vec![0; dictionary.values().len()];
+            for (i, key) in dictionary.normalized_keys().into_iter().enumerate() {
+                if is_valid(i) {
+                    key_counts[key] += 1;
+                }
+            }
+            let rows = converter.convert_columns(std::slice::from_ref(dictionary.values()))?;
+            for (key, count) in key_counts.into_iter().enumerate() {
+                if count > 0 {
+                    counter.add(rows.row(key).as_ref(), count);
+                }
+            }
+        } else {
+            let rows = converter.convert_columns(std::slice::from_ref(&array))?;
+            for (i, row) in rows.iter().enumerate() {
+                if is_valid(i) {
+                    counter.add(row.as_ref(), 1);
+                }
+            }
+        }
+    }
+
+    let mut counts: Vec<_> = counter.counts.into_iter().collect();
+    // Most frequent first, ties in value order
+    counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+    counts.truncate(k);
+
+    let parser = converter.parser();
+    let values = converter.convert_rows(counts.iter().map(|(key, _)| parser.parse(key)))?;
+    let values = counts
+        .iter()
+        .enumerate()
+        .map(|(i, (_, count))| {
+            Ok(ValueCount {
+                value: array_value_to_string(&values[0], i)?,
+                count: *count,
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(ValueCounts {
+        column: column.to_string(),
+        values,
+        row_count,
+        null_count,
+        max_error: counter.max_error,
+    })
+}
+
+/// Counts values exactly until there are too many distinct ones, then keeps
+/// the heavy hitters with the Misra-Gries algorithm.
+struct HeavyHitters {
+    counts: HashMap<Box<[u8]>, u64>,
+    /// Number of values kept when shrinking.
+    capacity: usize,
+    /// Total subtracted from every count by shrinking.
+    max_error: u64,
+}
+
+impl HeavyHitters {
+    fn new(capacity: usize) -> Self {
+        Self {
+            counts: HashMap::new(),
+            capacity,
+            max_error: 0,
+        }
+    }
+
+    fn add(&mut self, key: &[u8], count: u64) {
+        match self.counts.get_mut(key) {
+            Some(total) => *total += count,
+            None => {
+                self.counts.insert(key.into(), count);
+                if self.counts.len() > 2 * self.capacity {
+                    self.shrink();
+                }
+            }
+        }
+    }
+
+    /// Subtract the count of the first value beyond the capacity from every
+    /// count, dropping the values left without any.
+    fn shrink(&mut self) {
+        let mut counts: Vec<u64> = self.counts.values().copied().collect();
+        let (_, &mut threshold, _) = counts.select_nth_unstable_by(self.capacity, |a, b| b.cmp(a));
+        self.counts.retain(|_, count| {
+            *count -= (*count).min(threshold);
+            *count > 0
+        });
+        self.max_error += threshold;
+    }
+}
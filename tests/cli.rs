@@ -0,0 +1,79 @@
+use arrow::array::Int32Array;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use parquet::file::properties::WriterProperties;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+use std::sync::Arc;
+
+/// A file of ten row groups cut after the first half of its data, followed
+/// by its original footer, like an interrupted copy with the footer
+/// restored.
+fn truncated_file() -> Vec<u8> {
+    let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![Arc::new(Int32Array::from_iter_values(0..10_000))],
+    )
+    .unwrap();
+    let props = WriterProperties::builder()
+        .set_max_row_group_size(1000)
+        .build();
+    let mut bytes = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut bytes, schema, Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let footer_length = u32::from_le_bytes(bytes[bytes.len() - 8..][..4].try_into().unwrap());
+    let footer_start = bytes.len() - 8 - footer_length as usize;
+    let mut truncated = bytes[..footer_start / 2].to_vec();
+    truncated.extend_from_slice(&bytes[footer_start..]);
+    truncated
+}
+
+fn run_with_stdin(args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_parquet-viewer"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_truncated_stdin_fails_without_panicking() {
+    let bytes = truncated_file();
+
+    let output = run_with_stdin(&["data", "-g", "1", "-l", "1", "-"], &bytes);
+    assert!(output.status.success());
+
+    for args in [
+        &["data", "-g", "8", "-"][..],
+        &["data", "-o", "9500", "-"],
+        &["profile", "-"],
+    ] {
+        let output = run_with_stdin(args, &bytes);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(output.status.code(), Some(1), "{args:?}: {stderr}");
+        assert!(stderr.starts_with("Error: "), "{args:?}: {stderr}");
+        assert!(!stderr.contains("panicked"), "{args:?}: {stderr}");
+    }
+}
+
+#[test]
+fn test_inspect_levels_truncated_stdin() {
+    let output = run_with_stdin(
+        &["inspect", "levels", "-c", "a", "-g", "8", "-"],
+        &truncated_file(),
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1), "{stderr}");
+    assert!(
+        stderr.contains("Column chunk a of row group 8 lies outside the file"),
+        "{stderr}"
+    );
+}